@@ -1,17 +1,40 @@
 use std::io::{Read, Write};
+use std::path::Path;
 
-use super::Task;
+use super::{History, Snapshot, Task};
 
 pub struct PomoFile;
 
 impl PomoFile {
-    pub fn load() -> std::io::Result<Vec<Task>> {
-        let mut file = std::fs::File::open(".data/tasks")?;
+    pub fn load(dir: &Path) -> std::io::Result<Vec<Task>> {
+        let mut file = match std::fs::File::open(dir.join("tasks")) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
         let mut task_string = String::new();
 
         let _ = file.read_to_string(&mut task_string)?;
 
-        let tasks_data: Vec<&str> = task_string.as_str().split("\n").collect();
+        // Only files that aren't JSON at all are in the old format, a broken
+        // JSON file is an error rather than something to read as lines.
+        let tasks = if task_string.trim_start().starts_with('[') {
+            serde_json::from_str::<Vec<Task>>(&task_string)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        } else {
+            Self::load_legacy(&task_string)
+        };
+
+        if let Some(max_id) = tasks.iter().map(|task| task.id).max() {
+            Task::id_reserve(max_id);
+        }
+
+        Ok(tasks)
+    }
+
+    /// Reads the old `name:description` per line format.
+    fn load_legacy(task_string: &str) -> Vec<Task> {
+        let tasks_data: Vec<&str> = task_string.split('\n').collect();
 
         let mut tasks: Vec<Task> = vec![];
         for task_data in tasks_data {
@@ -19,28 +42,214 @@ impl PomoFile {
                 continue;
             }
 
-            let task_data: Vec<&str> = task_data.split(":").collect();
-            let task = Task::new(task_data[0], task_data[1]);
+            let task_data: Vec<&str> = task_data.split(':').collect();
+            let task = Task::new(task_data[0], *task_data.get(1).unwrap_or(&""));
             tasks.push(task);
         }
 
-        Ok(tasks)
+        tasks
     }
 
-    pub fn save(tasks: Vec<Task>) -> std::io::Result<()> {
-        let _ = Self::create_data_folder();
+    pub fn save(dir: &Path, tasks: Vec<Task>) -> std::io::Result<()> {
+        Self::create_data_folder(dir)?;
 
-        if let Ok(mut file) = std::fs::File::create(".data/tasks") {
-            for task in tasks {
-                file.write_all(format!("{}:{}\n", task.name, task.description).as_bytes())?;
-            }
+        Self::write(&dir.join("tasks"), &serde_json::to_string(&tasks)?)?;
+
+        Ok(())
+    }
+
+    pub fn history_load(dir: &Path) -> std::io::Result<History> {
+        let history_string = match std::fs::read_to_string(dir.join("history")) {
+            Ok(history_string) => history_string,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(History::default()),
+            Err(e) => return Err(e),
+        };
+
+        let history: History = serde_json::from_str(&history_string)?;
+        // Sessions keep the ids of removed tasks, which a new task mustn't
+        // take over along with their stats.
+        if let Some(max_id) = history.sessions.iter().filter_map(|s| s.task).max() {
+            Task::id_reserve(max_id);
         }
 
+        Ok(history)
+    }
+
+    pub fn history_save(dir: &Path, history: &History) -> std::io::Result<()> {
+        Self::create_data_folder(dir)?;
+
+        let history_string = serde_json::to_string(history)?;
+        Self::write(&dir.join("history"), &history_string)?;
+
+        Ok(())
+    }
+
+    pub fn archive_load(dir: &Path) -> std::io::Result<Vec<Task>> {
+        let archive_string = match std::fs::read_to_string(dir.join("archive")) {
+            Ok(archive_string) => archive_string,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let archive: Vec<Task> = serde_json::from_str(&archive_string)?;
+        if let Some(max_id) = archive.iter().map(|task| task.id).max() {
+            Task::id_reserve(max_id);
+        }
+
+        Ok(archive)
+    }
+
+    pub fn archive_save(dir: &Path, archive: &[Task]) -> std::io::Result<()> {
+        Self::create_data_folder(dir)?;
+
+        let archive_string = serde_json::to_string(archive)?;
+        Self::write(&dir.join("archive"), &archive_string)?;
+
+        Ok(())
+    }
+
+    pub fn snapshot_load(dir: &Path) -> std::io::Result<Option<Snapshot>> {
+        let snapshot_string = match std::fs::read_to_string(dir.join("timer")) {
+            Ok(snapshot_string) => snapshot_string,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Some(serde_json::from_str(&snapshot_string)?))
+    }
+
+    pub fn snapshot_save(dir: &Path, snapshot: &Snapshot) -> std::io::Result<()> {
+        Self::create_data_folder(dir)?;
+
+        let snapshot_string = serde_json::to_string(snapshot)?;
+        Self::write(&dir.join("timer"), &snapshot_string)?;
+
         Ok(())
     }
 
-    fn create_data_folder() -> std::io::Result<()> {
-        std::fs::create_dir(".data")?;
+    /// Writes `contents` to a temporary file next to `path` and renames it
+    /// over `path`, so a reader never sees a half-written file.
+    fn write(path: &Path, contents: &str) -> std::io::Result<()> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}.tmp", std::process::id()));
+        let temp = path.with_file_name(name);
+
+        let written = std::fs::File::create(&temp).and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        });
+        match written.and_then(|()| std::fs::rename(&temp, path)) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp);
+                Err(e)
+            }
+        }
+    }
+
+    fn create_data_folder(dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("tomatoes-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn should_reject_broken_json() {
+            let dir = dir("file-broken");
+            std::fs::write(dir.join("tasks"), r#"[{"id":1,"name":"Fir"#).unwrap();
+
+            let error = PomoFile::load(&dir).unwrap_err();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+
+        #[test]
+        fn should_read_the_legacy_format() {
+            let dir = dir("file-legacy");
+            std::fs::write(dir.join("tasks"), "First:Do it\nSecond\n").unwrap();
+
+            let tasks = PomoFile::load(&dir).unwrap();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert_eq!(tasks.len(), 2);
+            assert_eq!(tasks[0].name, "First");
+            assert_eq!(tasks[0].description, "Do it");
+        }
+    }
+
+    mod history_load {
+        use super::*;
+        use crate::backend::{Session, SessionOutcome, TimerType};
+
+        #[test]
+        fn should_not_reuse_the_ids_of_removed_tasks() {
+            let dir = dir("file-history-ids");
+            // The highest task of an earlier run, then removed.
+            let mut removed = Task::new("Removed", "");
+            removed.id += 1000;
+            PomoFile::save(&dir, vec![removed.clone()]).unwrap();
+            let mut session = Session::new(
+                TimerType::Focus,
+                std::time::Duration::from_secs(60),
+                SessionOutcome::Completed,
+            );
+            session.task = Some(removed.id);
+            let history = History {
+                sessions: vec![session],
+                ..History::default()
+            };
+            PomoFile::history_save(&dir, &history).unwrap();
+            PomoFile::save(&dir, vec![]).unwrap();
+
+            PomoFile::load(&dir).unwrap();
+            PomoFile::history_load(&dir).unwrap();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert!(Task::new("New", "").id > removed.id);
+        }
+    }
+
+    mod save {
+        use super::*;
+
+        #[test]
+        fn should_replace_the_file_whole() {
+            let dir = dir("file-save");
+            PomoFile::save(&dir, vec![Task::new("First", "")]).unwrap();
+            PomoFile::save(&dir, vec![]).unwrap();
+
+            let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+            let tasks = PomoFile::load(&dir).unwrap();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert_eq!(files.len(), 1);
+            assert!(tasks.is_empty());
+        }
+
+        #[test]
+        fn should_fail_when_the_dir_cant_be_created() {
+            let file = dir("file-save-blocked");
+            let _ = std::fs::remove_dir_all(&file);
+            std::fs::write(&file, "").unwrap();
+
+            let saved = PomoFile::save(&file.join("data"), vec![]);
+            let _ = std::fs::remove_file(&file);
+
+            assert!(saved.is_err());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/// Scores how well `pattern` matches `text`, ignoring case. Every char of the
+/// pattern has to appear in the text in order; substrings, runs of
+/// consecutive chars and matches at the start of words score higher.
+/// Returns `None` when it doesn't match. An empty pattern matches anything.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<u32> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut pattern_index = 0;
+    let mut prev_match: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if pattern_index == pattern.len() {
+            break;
+        }
+        if *c != pattern[pattern_index] {
+            continue;
+        }
+
+        score += 1;
+        if prev_match.is_some_and(|prev| prev + 1 == i) {
+            score += 4;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 2;
+        }
+        prev_match = Some(i);
+        pattern_index += 1;
+    }
+
+    if pattern_index < pattern.len() {
+        return None;
+    }
+
+    let pattern: String = pattern.into_iter().collect();
+    let text: String = text.into_iter().collect();
+    if text.contains(&pattern) {
+        score += 10;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod fuzzy_score {
+        use super::*;
+
+        #[test]
+        fn should_match_chars_in_order() {
+            assert!(fuzzy_score("wkrp", "Weekly report").is_some());
+            assert!(fuzzy_score("WEEK", "weekly report").is_some());
+            assert!(fuzzy_score("", "anything").is_some());
+            assert_eq!(fuzzy_score("prw", "Weekly report"), None);
+            assert_eq!(fuzzy_score("reports", "Weekly report"), None);
+        }
+
+        #[test]
+        fn should_rank_substrings_and_word_starts_higher() {
+            let substring = fuzzy_score("rep", "Weekly report").unwrap();
+            let scattered = fuzzy_score("rep", "Read the paper").unwrap();
+            assert!(substring > scattered);
+
+            let word_start = fuzzy_score("r", "Weekly report").unwrap();
+            let inside = fuzzy_score("r", "Inbox zero").unwrap();
+            assert!(word_start > inside);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::Duration;
+
+use super::history::{DayFocus, History};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DailyGoal {
+    Pomodoros(u32),
+    Focus(Duration),
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        DailyGoal::Pomodoros(8)
+    }
+}
+
+impl DailyGoal {
+    pub fn is_met(&self, day: &DayFocus) -> bool {
+        match self {
+            DailyGoal::Pomodoros(target) => day.pomodoros >= *target,
+            DailyGoal::Focus(target) => day.focus >= *target,
+        }
+    }
+
+    pub fn progress(&self, day: &DayFocus) -> String {
+        match self {
+            DailyGoal::Pomodoros(target) => format!("{}/{}", day.pomodoros, target),
+            DailyGoal::Focus(target) => {
+                format!("{}/{}", format_hours(day.focus), format_hours(*target))
+            }
+        }
+    }
+}
+
+impl fmt::Display for DailyGoal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DailyGoal::Pomodoros(target) => write!(f, "{target} pomodoros"),
+            DailyGoal::Focus(target) => write!(f, "{} focus", format_hours(*target)),
+        }
+    }
+}
+
+/// Formats a duration as `4h`, `1h40m` or `40m`.
+pub fn format_hours(duration: Duration) -> String {
+    let total_mins = duration.as_secs() / 60;
+    let (hours, mins) = (total_mins / 60, total_mins % 60);
+
+    match (hours, mins) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m:02}m"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    pub current: u32,
+    pub longest: u32,
+}
+
+impl Streaks {
+    /// A streak is a run of consecutive days meeting the goal. The current
+    /// streak stays alive through `today` until the day is over.
+    pub fn compute(history: &History, goal: &DailyGoal, today: NaiveDate) -> Self {
+        let met_days: BTreeSet<NaiveDate> = history
+            .focus_by_day()
+            .into_iter()
+            .filter(|(_, day)| goal.is_met(day))
+            .map(|(date, _)| date)
+            .collect();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut prev_day: Option<NaiveDate> = None;
+        for day in met_days.iter() {
+            run = match prev_day {
+                Some(prev) if prev.succ_opt() == Some(*day) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            prev_day = Some(*day);
+        }
+
+        let mut current = 0;
+        let mut day = if met_days.contains(&today) {
+            Some(today)
+        } else {
+            today.pred_opt()
+        };
+        while let Some(d) = day.filter(|d| met_days.contains(d)) {
+            current += 1;
+            day = d.pred_opt();
+        }
+
+        Self { current, longest }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::history::{Session, SessionOutcome};
+    use crate::backend::timer::TimerType;
+    use chrono::{Local, TimeZone};
+
+    const FOCUS_TIME: Duration = Duration::from_secs(25 * 60);
+
+    fn history_with(days: &[(u32, u32)]) -> History {
+        let mut history = History::default();
+        for (day, pomodoros) in days {
+            for _ in 0..*pomodoros {
+                let mut session =
+                    Session::new(TimerType::Focus, FOCUS_TIME, SessionOutcome::Completed);
+                session.ended_at = Local.with_ymd_and_hms(2024, 1, *day, 12, 0, 0).unwrap();
+                history.push(session);
+            }
+        }
+        history
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    mod is_met {
+        use super::*;
+
+        #[test]
+        fn should_compare_by_goal_kind() {
+            let day = DayFocus {
+                pomodoros: 4,
+                focus: FOCUS_TIME * 4,
+            };
+
+            assert!(DailyGoal::Pomodoros(4).is_met(&day));
+            assert!(!DailyGoal::Pomodoros(5).is_met(&day));
+            assert!(DailyGoal::Focus(Duration::from_secs(60 * 60)).is_met(&day));
+            assert!(!DailyGoal::Focus(Duration::from_secs(4 * 60 * 60)).is_met(&day));
+        }
+    }
+
+    mod progress {
+        use super::*;
+
+        #[test]
+        fn should_format_progress_by_goal_kind() {
+            let day = DayFocus {
+                pomodoros: 4,
+                focus: FOCUS_TIME * 4,
+            };
+
+            assert_eq!(DailyGoal::Pomodoros(8).progress(&day), "4/8");
+            assert_eq!(
+                DailyGoal::Focus(Duration::from_secs(4 * 60 * 60)).progress(&day),
+                "1h40m/4h"
+            );
+        }
+    }
+
+    mod compute {
+        use super::*;
+
+        #[test]
+        fn should_count_consecutive_days_meeting_the_goal() {
+            let history = history_with(&[(1, 2), (2, 2), (3, 2), (5, 2), (6, 2)]);
+            let streaks = Streaks::compute(&history, &DailyGoal::Pomodoros(2), date(6));
+
+            assert_eq!(streaks.current, 2);
+            assert_eq!(streaks.longest, 3);
+        }
+
+        #[test]
+        fn should_ignore_days_below_the_goal() {
+            let history = history_with(&[(1, 2), (2, 1), (3, 2)]);
+            let streaks = Streaks::compute(&history, &DailyGoal::Pomodoros(2), date(3));
+
+            assert_eq!(streaks.current, 1);
+            assert_eq!(streaks.longest, 1);
+        }
+
+        #[test]
+        fn should_keep_current_streak_while_today_is_not_met_yet() {
+            let history = history_with(&[(1, 2), (2, 2), (3, 1)]);
+            let streaks = Streaks::compute(&history, &DailyGoal::Pomodoros(2), date(3));
+
+            assert_eq!(streaks.current, 2);
+        }
+
+        #[test]
+        fn should_break_current_streak_after_a_missed_day() {
+            let history = history_with(&[(1, 2), (2, 2)]);
+            let streaks = Streaks::compute(&history, &DailyGoal::Pomodoros(2), date(4));
+
+            assert_eq!(streaks.current, 0);
+            assert_eq!(streaks.longest, 2);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use super::goal::format_hours;
+use super::timer::TimerType;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionOutcome {
+    Completed,
+    Skipped,
+    Reset,
+    Voided,
+}
+
+/// Internal interruptions come from ourselves (`'`), external ones from
+/// someone else (`-`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InterruptionKind {
+    Internal,
+    External,
+}
+
+impl InterruptionKind {
+    pub fn symbol(&self) -> char {
+        match self {
+            InterruptionKind::Internal => '\'',
+            InterruptionKind::External => '-',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Interruption {
+    pub kind: InterruptionKind,
+    pub at: DateTime<Local>,
+    pub note: Option<String>,
+}
+
+impl Interruption {
+    pub fn new(kind: InterruptionKind, note: Option<String>) -> Self {
+        Self {
+            kind,
+            at: Local::now(),
+            note,
+        }
+    }
+}
+
+/// A stretch of a focus block no one was at the keyboard for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Idle {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+}
+
+impl Idle {
+    pub fn duration(&self) -> Duration {
+        (self.to - self.from).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterruptionTotals {
+    pub internal: u32,
+    pub external: u32,
+    pub voided: u32,
+}
+
+impl InterruptionTotals {
+    pub fn count(interruptions: &[Interruption]) -> Self {
+        let mut totals = Self::default();
+        for interruption in interruptions {
+            match interruption.kind {
+                InterruptionKind::Internal => totals.internal += 1,
+                InterruptionKind::External => totals.external += 1,
+            }
+        }
+        totals
+    }
+}
+
+/// What a computer sleeping in the middle of a phase does to the timer.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuspendHandling {
+    /// The timer waits, as if it had been paused.
+    #[default]
+    Pause,
+    /// The time asleep counts, as if the timer had kept running.
+    Count,
+    /// The phase is thrown away and starts over.
+    Void,
+}
+
+/// A time the computer slept while the timer ran.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Suspend {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub mode: TimerType,
+    pub handling: SuspendHandling,
+}
+
+impl Suspend {
+    pub fn duration(&self) -> Duration {
+        (self.to - self.from).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+impl std::fmt::Display for Suspend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slept = match self.duration() {
+            duration if duration.as_secs() < 60 => format!("{}s", duration.as_secs()),
+            duration => format_hours(duration),
+        };
+        let handled = match self.handling {
+            SuspendHandling::Pause => "not counted",
+            SuspendHandling::Count => "counted",
+            SuspendHandling::Void => "started over",
+        };
+        let mode = self.mode.to_string().to_lowercase();
+        write!(f, "Slept {slept} during {mode}: {handled}")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Session {
+    pub mode: TimerType,
+    pub duration: Duration,
+    pub ended_at: DateTime<Local>,
+    pub outcome: SessionOutcome,
+    #[serde(default)]
+    pub task: Option<u64>,
+    #[serde(default)]
+    pub interruptions: Vec<Interruption>,
+    /// Time given back to the block because no one was there.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub idle: Vec<Idle>,
+}
+
+impl Session {
+    pub fn new(mode: TimerType, duration: Duration, outcome: SessionOutcome) -> Self {
+        Self {
+            mode,
+            duration,
+            ended_at: Local::now(),
+            outcome,
+            task: None,
+            interruptions: vec![],
+            idle: vec![],
+        }
+    }
+
+    /// Only focus blocks that ran until the end count toward the daily goal.
+    pub fn counts_toward_goal(&self) -> bool {
+        self.mode == TimerType::Focus && self.outcome == SessionOutcome::Completed
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DayFocus {
+    pub pomodoros: u32,
+    pub focus: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskStats {
+    pub pomodoros: u32,
+    pub focus: Duration,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct History {
+    pub sessions: Vec<Session>,
+    #[serde(default)]
+    pub suspends: Vec<Suspend>,
+}
+
+impl History {
+    pub fn push(&mut self, session: Session) {
+        self.sessions.push(session);
+    }
+
+    pub fn focus_by_day(&self) -> BTreeMap<NaiveDate, DayFocus> {
+        let mut days: BTreeMap<NaiveDate, DayFocus> = BTreeMap::new();
+
+        for session in self.sessions.iter().filter(|s| s.counts_toward_goal()) {
+            let day = days.entry(session.ended_at.date_naive()).or_default();
+            day.pomodoros += 1;
+            day.focus += session.duration;
+        }
+
+        days
+    }
+
+    pub fn focus_on(&self, date: NaiveDate) -> DayFocus {
+        self.focus_by_day().get(&date).copied().unwrap_or_default()
+    }
+
+    /// Completed pomodoros and total focus time spent on each task, by task id.
+    pub fn focus_by_task(&self) -> HashMap<u64, TaskStats> {
+        let mut tasks: HashMap<u64, TaskStats> = HashMap::new();
+
+        for session in self.sessions.iter().filter(|s| s.mode == TimerType::Focus) {
+            if let Some(task_id) = session.task {
+                let stats = tasks.entry(task_id).or_default();
+                stats.focus += session.duration;
+                if session.outcome == SessionOutcome::Completed {
+                    stats.pomodoros += 1;
+                }
+            }
+        }
+
+        tasks
+    }
+
+    pub fn interruption_totals(&self) -> InterruptionTotals {
+        let mut totals = InterruptionTotals::default();
+
+        for session in self.sessions.iter() {
+            let session_totals = InterruptionTotals::count(&session.interruptions);
+            totals.internal += session_totals.internal;
+            totals.external += session_totals.external;
+            if session.outcome == SessionOutcome::Voided {
+                totals.voided += 1;
+            }
+        }
+
+        totals
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    const FOCUS_TIME: Duration = Duration::from_secs(25 * 60);
+
+    fn session_at(day: u32, outcome: SessionOutcome) -> Session {
+        let mut session = Session::new(TimerType::Focus, FOCUS_TIME, outcome);
+        session.ended_at = Local.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap();
+        session
+    }
+
+    mod counts_toward_goal {
+        use super::*;
+
+        #[test]
+        fn should_only_count_completed_focus_sessions() {
+            let completed = Session::new(TimerType::Focus, FOCUS_TIME, SessionOutcome::Completed);
+            let skipped = Session::new(TimerType::Focus, FOCUS_TIME, SessionOutcome::Skipped);
+            let reset = Session::new(TimerType::Focus, FOCUS_TIME, SessionOutcome::Reset);
+            let rest = Session::new(TimerType::Rest, FOCUS_TIME, SessionOutcome::Completed);
+
+            assert!(completed.counts_toward_goal());
+            assert!(!skipped.counts_toward_goal());
+            assert!(!reset.counts_toward_goal());
+            let voided = Session::new(TimerType::Focus, FOCUS_TIME, SessionOutcome::Voided);
+            assert!(!voided.counts_toward_goal());
+            assert!(!rest.counts_toward_goal());
+        }
+    }
+
+    mod focus_by_day {
+        use super::*;
+
+        #[test]
+        fn should_group_completed_sessions_by_day() {
+            let mut history = History::default();
+            history.push(session_at(1, SessionOutcome::Completed));
+            history.push(session_at(1, SessionOutcome::Completed));
+            history.push(session_at(1, SessionOutcome::Skipped));
+            history.push(session_at(2, SessionOutcome::Completed));
+
+            let days = history.focus_by_day();
+            let first_day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let second_day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+            assert_eq!(days.len(), 2);
+            assert_eq!(days[&first_day].pomodoros, 2);
+            assert_eq!(days[&first_day].focus, FOCUS_TIME * 2);
+            assert_eq!(days[&second_day].pomodoros, 1);
+        }
+    }
+
+    mod focus_by_task {
+        use super::*;
+
+        #[test]
+        fn should_sum_focus_sessions_by_task() {
+            let mut history = History::default();
+            for (task, outcome) in [
+                (Some(1), SessionOutcome::Completed),
+                (Some(1), SessionOutcome::Completed),
+                (Some(1), SessionOutcome::Skipped),
+                (Some(2), SessionOutcome::Completed),
+                (None, SessionOutcome::Completed),
+            ] {
+                let mut session = session_at(1, outcome);
+                session.task = task;
+                history.push(session);
+            }
+
+            let tasks = history.focus_by_task();
+
+            assert_eq!(tasks.len(), 2);
+            assert_eq!(tasks[&1].pomodoros, 2);
+            assert_eq!(tasks[&1].focus, FOCUS_TIME * 3);
+            assert_eq!(tasks[&2].pomodoros, 1);
+        }
+    }
+
+    mod interruption_totals {
+        use super::*;
+
+        #[test]
+        fn should_sum_interruptions_and_voided_sessions() {
+            let mut history = History::default();
+
+            let mut session = session_at(1, SessionOutcome::Completed);
+            session.interruptions = vec![
+                Interruption::new(InterruptionKind::Internal, None),
+                Interruption::new(InterruptionKind::External, Some("Call".to_string())),
+            ];
+            history.push(session);
+
+            let mut session = session_at(1, SessionOutcome::Voided);
+            session.interruptions = vec![Interruption::new(InterruptionKind::External, None)];
+            history.push(session);
+
+            let totals = history.interruption_totals();
+            assert_eq!(totals.internal, 1);
+            assert_eq!(totals.external, 2);
+            assert_eq!(totals.voided, 1);
+        }
+    }
+
+    mod suspend {
+        use super::*;
+
+        #[test]
+        fn should_say_how_long_and_what_came_of_it() {
+            let from = Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+            let mut suspend = Suspend {
+                from,
+                to: from + chrono::Duration::minutes(62),
+                mode: TimerType::Focus,
+                handling: SuspendHandling::Pause,
+            };
+            assert_eq!(suspend.to_string(), "Slept 1h02m during focus: not counted");
+
+            suspend.to = from + chrono::Duration::seconds(40);
+            suspend.mode = TimerType::Rest;
+            suspend.handling = SuspendHandling::Void;
+            assert_eq!(suspend.to_string(), "Slept 40s during rest: started over");
+        }
+
+        #[test]
+        fn should_read_histories_saved_before_suspends() {
+            let history: History = serde_json::from_str(r#"{"sessions":[]}"#).unwrap();
+
+            assert!(history.suspends.is_empty());
+        }
+    }
+}
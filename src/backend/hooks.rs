@@ -0,0 +1,265 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::history::SessionOutcome;
+use super::task::Task;
+
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// A focus block started running from the beginning.
+    FocusStart,
+    /// A focus block ended, whether it ran out or was skipped, reset or
+    /// voided.
+    FocusEnd,
+    BreakStart,
+    BreakEnd,
+    TaskDone,
+}
+
+impl HookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::FocusStart => "focus_start",
+            HookEvent::FocusEnd => "focus_end",
+            HookEvent::BreakStart => "break_start",
+            HookEvent::BreakEnd => "break_end",
+            HookEvent::TaskDone => "task_done",
+        }
+    }
+}
+
+/// What a hook is told about its event, as JSON on stdin and in
+/// `TOMATOES_*` environment variables.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct HookData {
+    pub event: HookEvent,
+    pub at: DateTime<Local>,
+    /// Seconds the phase lasts on a start, or ran for on an end.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    /// How the phase ended: `completed`, `skipped`, `reset` or `voided`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<&'static str>,
+    /// The active task, or the one done for `task_done`.
+    pub task: Option<Task>,
+}
+
+impl HookData {
+    pub fn new(event: HookEvent, task: Option<Task>) -> Self {
+        Self {
+            event,
+            at: Local::now(),
+            duration: None,
+            outcome: None,
+            task,
+        }
+    }
+
+    pub fn phase(mut self, duration: Duration, outcome: Option<SessionOutcome>) -> Self {
+        self.duration = Some(duration.as_secs());
+        self.outcome = outcome.map(|outcome| match outcome {
+            SessionOutcome::Completed => "completed",
+            SessionOutcome::Skipped => "skipped",
+            SessionOutcome::Reset => "reset",
+            SessionOutcome::Voided => "voided",
+        });
+        self
+    }
+
+    fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("TOMATOES_EVENT", self.event.name().to_string())];
+        if let Some(duration) = self.duration {
+            env.push(("TOMATOES_DURATION", duration.to_string()));
+        }
+        if let Some(outcome) = self.outcome {
+            env.push(("TOMATOES_OUTCOME", outcome.to_string()));
+        }
+        if let Some(task) = &self.task {
+            env.push(("TOMATOES_TASK_ID", task.id.to_string()));
+            env.push(("TOMATOES_TASK", task.name.clone()));
+        }
+        env
+    }
+}
+
+/// Shell commands run on timer and task events. Each is waited on by its
+/// own thread, so a slow one holds neither the timer nor the UI up, and is
+/// killed after the timeout.
+#[derive(Debug, Clone)]
+pub struct Hooks {
+    commands: HashMap<HookEvent, String>,
+    timeout: Duration,
+    /// The threads waiting on hooks, for [`Hooks::wait`].
+    running: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self::new(HOOK_TIMEOUT)
+    }
+}
+
+impl Hooks {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            commands: HashMap::new(),
+            timeout,
+            running: Arc::default(),
+        }
+    }
+
+    pub fn set(&mut self, event: HookEvent, command: impl Into<String>) {
+        self.commands.insert(event, command.into());
+    }
+
+    /// Starts the command for `data.event`, if there is one, without
+    /// waiting for it. Its output and failures are dropped.
+    pub fn run(&self, data: HookData) {
+        let Some(command) = self.commands.get(&data.event).cloned() else {
+            return;
+        };
+        // Started here rather than on the thread, so that it runs even when
+        // the program exits right after.
+        let Ok(child) = hook_spawn(&command, &data) else {
+            return;
+        };
+        let timeout = self.timeout;
+        let waiter = thread::spawn(move || {
+            let _ = hook_wait(child, &command, timeout);
+        });
+
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.retain(|waiter| !waiter.is_finished());
+        running.push(waiter);
+    }
+
+    /// Waits for the hooks still running, each for up to the timeout. To
+    /// call before exiting, which would leave them without their stdin.
+    pub fn wait(&self) {
+        let running = std::mem::take(&mut *self.running.lock().unwrap_or_else(|e| e.into_inner()));
+        for waiter in running {
+            let _ = waiter.join();
+        }
+    }
+}
+
+/// Starts `command` with `sh -c`, writing `data` to its stdin aside.
+fn hook_spawn(command: &str, data: &HookData) -> io::Result<(Child, JoinHandle<()>)> {
+    let json = serde_json::to_string(data)?;
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(data.env())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+
+    // Written aside, as a hook that doesn't read its stdin could block us.
+    let stdin = child.stdin.take();
+    let writer = thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(json.as_bytes());
+        }
+    });
+    Ok((child, writer))
+}
+
+/// Waits for a hook from [`hook_spawn`] for up to `timeout`, then kills it.
+fn hook_wait(
+    (mut child, writer): (Child, JoinHandle<()>),
+    command: &str,
+    timeout: Duration,
+) -> io::Result<ExitStatus> {
+    let status = hook_status(&mut child, command, timeout);
+    // Done once the hook exited or was killed, which closes the pipe.
+    let _ = writer.join();
+    status
+}
+
+fn hook_status(child: &mut Child, command: &str, timeout: Duration) -> io::Result<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            // `sh` may have started commands of its own, so its whole group
+            // goes.
+            let group = format!("-{}", child.id());
+            let killed = Command::new("kill")
+                .args(["-KILL", "--", &group])
+                .stderr(Stdio::null())
+                .status();
+            if !killed.is_ok_and(|status| status.success()) {
+                child.kill()?;
+            }
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("hook `{command}` ran for more than {timeout:?}"),
+            ));
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn data() -> HookData {
+        HookData::new(HookEvent::FocusEnd, Some(Task::new("Write report", "")))
+            .phase(Duration::from_secs(1500), Some(SessionOutcome::Completed))
+    }
+
+    mod hook_wait {
+        use super::*;
+
+        fn hook_run(command: &str, timeout: Duration) -> io::Result<ExitStatus> {
+            hook_wait(hook_spawn(command, &data())?, command, timeout)
+        }
+
+        #[test]
+        fn should_pass_the_event_in_env_and_stdin() {
+            let path = std::env::temp_dir().join(format!("tomatoes-hook-{}", std::process::id()));
+            let command = format!(
+                "{{ echo \"$TOMATOES_EVENT $TOMATOES_DURATION $TOMATOES_OUTCOME $TOMATOES_TASK\"; cat; }} > {}",
+                path.display()
+            );
+
+            let status = hook_run(&command, HOOK_TIMEOUT).unwrap();
+            let output = std::fs::read_to_string(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert!(status.success());
+            let (env, json) = output.split_once('\n').unwrap();
+            assert_eq!(env, "focus_end 1500 completed Write report");
+            let json: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(json["event"], "focus_end");
+            assert_eq!(json["outcome"], "completed");
+            assert_eq!(json["task"]["name"], "Write report");
+        }
+
+        #[test]
+        fn should_kill_slow_hooks() {
+            let started = Instant::now();
+
+            let error = hook_run("sleep 5; true", Duration::from_millis(100)).unwrap_err();
+
+            assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+            assert!(started.elapsed() < Duration::from_secs(2));
+        }
+    }
+}
@@ -1,8 +1,25 @@
 mod file;
+mod fuzzy;
+mod goal;
+mod history;
+mod hooks;
 mod pomodoro;
+mod preset;
+mod report;
+mod snapshot;
+mod suspend;
 mod task;
 mod timer;
+mod undo;
 
+pub use fuzzy::*;
+pub use goal::*;
+pub use history::*;
+pub use hooks::*;
 pub use pomodoro::*;
+pub use preset::*;
+pub use report::*;
+pub use snapshot::*;
+pub use suspend::*;
 pub use task::*;
 pub use timer::*;
@@ -1,17 +1,64 @@
 use super::file::PomoFile;
+use super::fuzzy::fuzzy_score;
+use super::goal::*;
+use super::history::*;
+use super::hooks::*;
+use super::preset::*;
+use super::report::*;
+use super::snapshot::*;
 use super::task::*;
 use super::timer::*;
+use super::undo::*;
 
+use chrono::{DateTime, Local, NaiveDate};
 use rodio::OutputStream;
 
-use std::{io::BufReader, ops::Deref, time::Duration};
+use std::{
+    cell::{Cell, OnceCell},
+    collections::{HashMap, HashSet},
+    io::BufReader,
+    ops::Deref,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const DATA_DIR: &str = ".data";
+pub const ALARM_SOUND: &str = "assets/sounds/clock-alarm-8761.mp3";
 
 pub struct Pomodoro {
     focus: Timer,
     rest: Timer,
+    preset: Preset,
+    presets: Vec<Preset>,
+    focus_count: u32,
     tasks: Vec<Task>,
+    archive: Vec<Task>,
+    archive_after_days: Option<u32>,
     timer: TimerType,
     play_sound_alarm: bool,
+    alarm_sound: PathBuf,
+    alarm_error: Option<String>,
+    data_dir: PathBuf,
+    history: History,
+    goal: DailyGoal,
+    /// Today's focus and the streaks, worked out again only once the
+    /// history, the goal or the date change, as the TUI asks every frame.
+    goal_cache: Cell<Option<(NaiveDate, DayFocus, Streaks)>>,
+    /// The focus per task, worked out again once the history changes.
+    stats_cache: OnceCell<HashMap<u64, TaskStats>>,
+    /// The ids of the not completed tasks, which blocked tasks wait on,
+    /// worked out again once the tasks change.
+    open_cache: OnceCell<HashSet<u64>>,
+    active_task: Option<u64>,
+    interruptions: Vec<Interruption>,
+    idle: Vec<Idle>,
+    hooks: Hooks,
+    sort_by_priority: bool,
+    filter: Option<TaskFilter>,
+    auto_complete_parent: bool,
+    commands: UndoStack,
+    changes: Vec<Change>,
+    revision: u64,
 }
 
 impl Pomodoro {
@@ -19,48 +66,372 @@ impl Pomodoro {
         Self {
             focus: Timer::new(focus_time),
             rest: Timer::new(rest_time),
+            preset: Preset::new(DEFAULT_PRESET, focus_time, rest_time),
+            presets: vec![],
+            focus_count: 0,
             tasks: vec![],
+            archive: vec![],
+            archive_after_days: Some(7),
             timer: TimerType::Focus,
             play_sound_alarm: true,
+            alarm_sound: PathBuf::from(ALARM_SOUND),
+            alarm_error: None,
+            data_dir: PathBuf::from(DATA_DIR),
+            history: History::default(),
+            goal: DailyGoal::default(),
+            goal_cache: Cell::new(None),
+            stats_cache: OnceCell::new(),
+            open_cache: OnceCell::new(),
+            active_task: None,
+            interruptions: vec![],
+            idle: vec![],
+            hooks: Hooks::default(),
+            sort_by_priority: false,
+            filter: None,
+            auto_complete_parent: true,
+            commands: UndoStack::default(),
+            changes: vec![],
+            revision: 0,
         }
     }
 
+    /// Reads the tasks, archive and history, each on its own so a bad file
+    /// doesn't keep the others from loading. Returns the first error.
     pub fn load(&mut self) -> std::io::Result<()> {
-        self.tasks = PomoFile::load()?;
-        Ok(())
+        let history = self.history_load();
+        let tasks = self.tasks_load();
+        history.and(tasks)
     }
 
     pub fn save(&self) -> std::io::Result<()> {
-        PomoFile::save(self.task_get_by_complete(false))?;
+        self.tasks_save()?;
+        self.history_save()
+    }
+
+    /// Reads the tasks and the archive again, dropping the undo history
+    /// as its indexes may not match anymore.
+    pub fn tasks_load(&mut self) -> std::io::Result<()> {
+        let archive = PomoFile::archive_load(&self.data_dir).map(|archive| self.archive = archive);
+        let tasks = PomoFile::load(&self.data_dir).map(|tasks| self.tasks = tasks);
+        self.open_cache.take();
+        // Old tasks only move to an archive that was read.
+        if archive.is_ok() && tasks.is_ok() {
+            self.archive_old(Local::now().date_naive());
+        }
+        self.commands.clear();
+        archive.and(tasks)
+    }
+
+    pub fn tasks_save(&self) -> std::io::Result<()> {
+        PomoFile::save(&self.data_dir, self.tasks.clone())?;
+        PomoFile::archive_save(&self.data_dir, &self.archive)
+    }
+
+    pub fn history_load(&mut self) -> std::io::Result<()> {
+        self.history = PomoFile::history_load(&self.data_dir)?;
+        self.goal_cache.set(None);
+        self.stats_cache.take();
+        self.commands.clear();
         Ok(())
     }
 
+    pub fn history_save(&self) -> std::io::Result<()> {
+        PomoFile::history_save(&self.data_dir, &self.history)
+    }
+
+    /// The running timer, to save with `snapshot_save`.
+    pub fn snapshot(&self, paused: bool) -> Snapshot {
+        let mode = match &self.timer {
+            TimerType::Transitioning(next) => next.as_ref().clone(),
+            mode => mode.clone(),
+        };
+        Snapshot {
+            mode,
+            timer: self.get_current_timer(),
+            paused,
+            preset: self.preset.name.clone(),
+            focus_count: self.focus_count,
+            active_task: self.active_task,
+            interruptions: self.interruptions.clone(),
+            idle: self.idle.clone(),
+            saved_at: Local::now(),
+        }
+    }
+
+    pub fn snapshot_save(&self, paused: bool) -> std::io::Result<()> {
+        PomoFile::snapshot_save(&self.data_dir, &self.snapshot(paused))
+    }
+
+    /// The timer saved by the last `snapshot_save`, if any.
+    pub fn snapshot_load(&self) -> std::io::Result<Option<Snapshot>> {
+        PomoFile::snapshot_load(&self.data_dir)
+    }
+
+    /// Puts the timer back where `snapshot` left it, then runs it for the
+    /// time that went by until `now` when it wasn't paused. A phase that
+    /// ended in between is recorded as completed, and the timer stops at the
+    /// start of the next one. Returns whether the timer is paused.
+    pub fn resume(&mut self, snapshot: &Snapshot, now: DateTime<Local>) -> bool {
+        if let Some(preset) = self.presets.iter().find(|p| p.name == snapshot.preset) {
+            self.preset = preset.clone();
+        }
+        self.focus = Timer::new(self.preset.focus);
+        self.rest = Timer::new(self.preset.rest);
+        self.focus_count = snapshot.focus_count;
+        self.active_task = snapshot
+            .active_task
+            .filter(|id| self.tasks.iter().any(|task| task.id == *id));
+        self.interruptions = snapshot.interruptions.clone();
+        self.idle = snapshot.idle.clone();
+        self.timer = snapshot.mode.clone();
+        match self.timer {
+            TimerType::Focus => self.focus = snapshot.timer,
+            _ => {
+                self.timer = TimerType::Rest;
+                self.rest = snapshot.timer;
+            }
+        }
+
+        // The timer ticks by whole seconds, and a fraction left on it would
+        // never reach zero.
+        let elapsed = Duration::from_secs(snapshot.elapsed(now).as_secs());
+        self.catch_up(elapsed, now) || snapshot.paused
+    }
+
+    /// Handles the computer sleeping from `from` to `to` while the timer
+    /// ran, as `handling` says, and logs it in the history. Returns whether
+    /// the timer is paused afterwards, which counting the time does at the
+    /// end of a phase.
+    pub fn suspend(
+        &mut self,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        handling: SuspendHandling,
+    ) -> bool {
+        if let TimerType::Transitioning(next) = &self.timer {
+            self.timer = next.as_ref().clone();
+        }
+        let suspend = Suspend {
+            from,
+            to,
+            mode: self.timer.clone(),
+            handling,
+        };
+        let slept = Duration::from_secs(suspend.duration().as_secs());
+        self.history.suspends.push(suspend);
+
+        match handling {
+            SuspendHandling::Pause => false,
+            SuspendHandling::Count => self.catch_up(slept, to),
+            SuspendHandling::Void => {
+                match self.timer {
+                    TimerType::Focus => self.void_current(),
+                    _ => self.reset_timer(TimerType::Rest),
+                }
+                false
+            }
+        }
+    }
+
+    /// Counts `elapsed` that went by in one go, like while an editor had the
+    /// terminal, as `catch_up` does. Returns whether the timer stopped at
+    /// the start of the next phase.
+    pub fn run_for(&mut self, elapsed: Duration) -> bool {
+        if let TimerType::Transitioning(next) = &self.timer {
+            self.timer = next.as_ref().clone();
+        }
+        self.catch_up(elapsed, Local::now())
+    }
+
+    /// Runs the timer for `elapsed`, up to the end of the running phase,
+    /// which is recorded as completed if it ended before `now`. Nobody was
+    /// there to start the phases after it, so the timer stops at the start
+    /// of the next one and the rest of `elapsed` goes unrecorded. Returns
+    /// whether it stopped there.
+    fn catch_up(&mut self, elapsed: Duration, now: DateTime<Local>) -> bool {
+        let timer = self.get_current_timer();
+        if elapsed < timer.current_time {
+            match self.timer {
+                TimerType::Focus => self.focus.current_time -= elapsed,
+                _ => self.rest.current_time -= elapsed,
+            }
+            return false;
+        }
+
+        let after_end = elapsed - timer.current_time;
+        let ended_at = now - chrono::Duration::from_std(after_end).unwrap_or_default();
+        self.session_record_at(
+            self.timer.clone(),
+            timer.elapsed() + timer.current_time,
+            SessionOutcome::Completed,
+            ended_at,
+        );
+        if self.timer == TimerType::Focus {
+            self.focus = Timer::new(self.focus.initial_time);
+            self.rest_prepare(true);
+            self.timer = TimerType::Rest;
+        } else {
+            self.rest = Timer::new(self.rest.initial_time);
+            self.timer = TimerType::Focus;
+        }
+        true
+    }
+
+    /// Goes up with every command, undo and redo, so callers can tell when
+    /// there is something new to save.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Where `load` and `save` keep the tasks, history and archive.
+    pub fn data_dir_set(&mut self, data_dir: impl Into<PathBuf>) {
+        self.data_dir = data_dir.into();
+    }
+
+    pub fn data_dir_get(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Makes every `every`th rest after a completed focus block last
+    /// `long_rest_time`. An `every` of 0 turns long rests off.
+    pub fn long_rest_set(&mut self, long_rest_time: Duration, every: u32) {
+        self.preset.long_rest = long_rest_time;
+        self.preset.long_rest_every = every;
+        self.presets_update();
+    }
+
+    /// Whether the current or next rest is a long one.
+    pub fn rest_is_long(&self) -> bool {
+        self.preset.long_rest_every > 0 && self.rest.initial_time == self.preset.long_rest
+    }
+
+    /// Presets that `preset_apply` can switch to. The current preset keeps
+    /// its durations until it is applied again.
+    pub fn presets_set(&mut self, presets: Vec<Preset>) {
+        self.presets = presets;
+    }
+
+    pub fn presets_get(&self) -> &[Preset] {
+        &self.presets
+    }
+
+    pub fn preset_get(&self) -> &Preset {
+        &self.preset
+    }
+
+    /// Keeps the listed preset of the current name in sync with it.
+    fn presets_update(&mut self) {
+        let preset = self.preset.clone();
+        if let Some(listed) = self.presets.iter_mut().find(|p| p.name == preset.name) {
+            *listed = preset;
+        }
+    }
+
+    /// Switches to the preset called `name`, rebuilding both timers. The
+    /// running phase changes as `change` says, the other one starts over.
+    /// Returns `false` when there is no such preset.
+    pub fn preset_apply(&mut self, name: &str, change: PhaseChange) -> bool {
+        let preset = match self.presets.iter().find(|preset| preset.name == name) {
+            Some(preset) => preset.clone(),
+            None => return false,
+        };
+
+        self.timer_command(format!("preset {name}"), |pomodoro| {
+            let rest_time = if pomodoro.rest_is_long() {
+                preset.long_rest
+            } else {
+                preset.rest
+            };
+            let (focus_change, rest_change) = match pomodoro.timer {
+                TimerType::Focus => (change, PhaseChange::Restart),
+                TimerType::Rest => (PhaseChange::Restart, change),
+                TimerType::Transitioning(_) => (PhaseChange::Restart, PhaseChange::Restart),
+            };
+
+            pomodoro.focus = focus_change.apply(pomodoro.focus, preset.focus);
+            pomodoro.rest = rest_change.apply(pomodoro.rest, rest_time);
+            pomodoro.preset = preset;
+        });
+        true
+    }
+
+    /// Mirrors a timer that runs somewhere else, like in `tomatoesd`,
+    /// without recording sessions or undo steps.
+    pub fn timer_sync(&mut self, timer_type: TimerType, timer: Timer, preset: &str) {
+        if let Some(preset) = self.presets.iter().find(|p| p.name == preset) {
+            self.preset = preset.clone();
+        }
+        match timer_type {
+            TimerType::Focus => self.focus = timer,
+            TimerType::Rest => self.rest = timer,
+            TimerType::Transitioning(_) => return,
+        }
+        self.timer = timer_type;
+    }
+
+    /// Sets up the rest that follows a focus block, long when enough focus
+    /// blocks were completed since the last long rest.
+    fn rest_prepare(&mut self, focus_completed: bool) {
+        if focus_completed {
+            self.focus_count += 1;
+        }
+
+        let long =
+            self.preset.long_rest_every > 0 && self.focus_count >= self.preset.long_rest_every;
+        let rest_time = if long {
+            self.focus_count = 0;
+            self.preset.long_rest
+        } else {
+            self.preset.rest
+        };
+        self.rest = Timer::new(rest_time);
+    }
+
     const ONE_SEC: Duration = Duration::from_secs(1);
     pub fn forward(&mut self) -> Duration {
         match &self.timer {
             TimerType::Focus => {
-                self.focus.current_time -= Self::ONE_SEC;
+                if self.focus.current_time == self.focus.initial_time {
+                    self.hook_run(HookEvent::FocusStart, self.focus.initial_time, None);
+                }
+                self.focus.current_time = self.focus.current_time.saturating_sub(Self::ONE_SEC);
 
-                if self.focus.current_time == Duration::ZERO {
+                if self.focus.current_time <= Duration::ZERO {
+                    self.session_record(
+                        TimerType::Focus,
+                        self.focus.elapsed(),
+                        SessionOutcome::Completed,
+                    );
+
+                    self.rest_prepare(true);
                     let mut new_timer = TimerType::Rest;
                     if self.play_sound_alarm {
                         new_timer = TimerType::Transitioning(Box::new(new_timer));
                     }
                     self.timer = new_timer;
-                    self.focus.current_time = self.focus.initial_time
+                    self.focus = Timer::new(self.focus.initial_time);
                 }
                 self.focus.current_time
             }
             TimerType::Rest => {
-                self.rest.current_time -= Self::ONE_SEC;
+                if self.rest.current_time == self.rest.initial_time {
+                    self.hook_run(HookEvent::BreakStart, self.rest.initial_time, None);
+                }
+                self.rest.current_time = self.rest.current_time.saturating_sub(Self::ONE_SEC);
+
+                if self.rest.current_time <= Duration::ZERO {
+                    self.session_record(
+                        TimerType::Rest,
+                        self.rest.elapsed(),
+                        SessionOutcome::Completed,
+                    );
 
-                if self.rest.current_time == Duration::ZERO {
                     let mut new_timer = TimerType::Focus;
                     if self.play_sound_alarm {
                         new_timer = TimerType::Transitioning(Box::new(new_timer));
                     }
                     self.timer = new_timer;
-                    self.rest.current_time = self.rest.initial_time
+                    self.rest = Timer::new(self.rest.initial_time);
                 }
                 self.rest.current_time
             }
@@ -74,7 +445,9 @@ impl Pomodoro {
                 };
 
                 if self.play_sound_alarm && std::env::var("ENV") != Ok("TEST".to_string()) {
-                    self.alarm_play();
+                    if let Err(e) = self.alarm_play() {
+                        self.alarm_error = Some(e.to_string());
+                    }
                 }
 
                 self.timer = s.deref().clone();
@@ -83,193 +456,926 @@ impl Pomodoro {
         }
     }
 
-    pub fn alarm_play(&self) {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-        let file = std::fs::File::open("assets/sounds/clock-alarm-8761.mp3").unwrap();
-        let beep = stream_handle.play_once(BufReader::new(file)).unwrap();
+    pub fn alarm_play(&self) -> Result<(), Box<dyn std::error::Error>> {
         // NOTE: Maybe switch to a non-blocking approach
-        beep.sleep_until_end();
+        sound_play(&self.alarm_sound)
+    }
+
+    /// Why the alarm last failed to play, if it did since the last call.
+    pub fn alarm_error_take(&mut self) -> Option<String> {
+        self.alarm_error.take()
     }
 
     pub fn alarm_disable(&mut self) {
         self.play_sound_alarm = false;
     }
 
+    pub fn alarm_get(&self) -> bool {
+        self.play_sound_alarm
+    }
+
+    pub fn alarm_sound_get(&self) -> &Path {
+        &self.alarm_sound
+    }
+
+    pub fn alarm_sound_set(&mut self, alarm_sound: impl Into<PathBuf>) {
+        self.alarm_sound = alarm_sound.into();
+    }
+
+    /// Turns the alarm on or off. Returns whether it is now on.
+    pub fn alarm_toggle(&mut self) -> bool {
+        self.play_sound_alarm = !self.play_sound_alarm;
+        self.play_sound_alarm
+    }
+
     pub fn next_mode(&mut self) {
-        self.reset_timer(self.timer.clone());
-        match &self.timer {
-            TimerType::Focus => {
-                self.timer = TimerType::Rest;
-            }
-            TimerType::Rest => {
-                self.timer = TimerType::Focus;
-            }
-            TimerType::Transitioning(_) => {
-                unreachable!()
+        let description = format!("skip {}", self.timer).to_lowercase();
+        self.timer_command(description, |pomodoro| {
+            pomodoro.session_end(SessionOutcome::Skipped);
+            pomodoro.timer_restore(pomodoro.timer.clone());
+            match &pomodoro.timer {
+                TimerType::Focus => {
+                    pomodoro.rest_prepare(false);
+                    pomodoro.timer = TimerType::Rest;
+                }
+                TimerType::Rest => {
+                    pomodoro.timer = TimerType::Focus;
+                }
+                TimerType::Transitioning(_) => {
+                    unreachable!()
+                }
             }
+        });
+    }
+
+    /// Throws away the running focus block, e.g. after an interruption that
+    /// broke it, and restarts it.
+    pub fn void_current(&mut self) {
+        if self.timer == TimerType::Focus {
+            self.timer_command("void pomodoro".to_string(), |pomodoro| {
+                pomodoro.session_end(SessionOutcome::Voided);
+                pomodoro.timer_restore(TimerType::Focus);
+            });
+        }
+    }
+
+    /// Logs an interruption against the running focus block. Returns `false`
+    /// outside of focus.
+    pub fn interruption_add(&mut self, kind: InterruptionKind, note: Option<String>) -> bool {
+        if self.timer != TimerType::Focus {
+            return false;
+        }
+
+        self.interruptions.push(Interruption::new(kind, note));
+        true
+    }
+
+    /// Gives the running focus block back the time from `from` to `to`, when
+    /// no one was at the keyboard, and logs the gap with its session.
+    /// Returns `false` outside of focus.
+    pub fn idle_pause(&mut self, from: DateTime<Local>, to: DateTime<Local>) -> bool {
+        if self.timer != TimerType::Focus {
+            return false;
         }
+
+        let idle = Idle { from, to };
+        let idle_secs = Duration::from_secs(idle.duration().as_secs());
+        self.timer_command("idle pause".to_string(), |pomodoro| {
+            let focus = &mut pomodoro.focus;
+            focus.current_time = (focus.current_time + idle_secs).min(focus.initial_time);
+            pomodoro.idle.push(idle);
+        });
+        true
+    }
+
+    pub fn interruptions_current(&self) -> InterruptionTotals {
+        InterruptionTotals::count(&self.interruptions)
     }
 
     pub fn reset_timer(&mut self, timer_type: TimerType) {
+        let description = format!("reset {timer_type}").to_lowercase();
+        self.timer_command(description, |pomodoro| {
+            if timer_type == pomodoro.timer {
+                pomodoro.session_end(SessionOutcome::Reset);
+            }
+            pomodoro.timer_restore(timer_type);
+        });
+    }
+
+    fn timer_restore(&mut self, timer_type: TimerType) {
         match timer_type {
-            TimerType::Rest => self.rest.current_time = self.rest.initial_time,
-            TimerType::Focus => self.focus.current_time = self.focus.initial_time,
+            TimerType::Rest => self.rest = Timer::new(self.rest.initial_time),
+            TimerType::Focus => self.focus = Timer::new(self.focus.initial_time),
             TimerType::Transitioning(_) => {
                 unreachable!()
             }
         };
     }
 
-    pub fn get_mode(&self) -> TimerType {
-        self.timer.clone()
-    }
+    /// Records the elapsed part of the running block, if any, as an
+    /// interrupted session.
+    fn session_end(&mut self, outcome: SessionOutcome) {
+        let timer = match self.timer {
+            TimerType::Focus => self.focus,
+            TimerType::Rest => self.rest,
+            TimerType::Transitioning(_) => return,
+        };
 
-    pub fn task_add(&mut self, new_task: Task) {
-        self.tasks.push(new_task);
+        let elapsed = timer.elapsed();
+        if elapsed > Duration::ZERO || !self.interruptions.is_empty() || !self.idle.is_empty() {
+            self.session_record(self.timer.clone(), elapsed, outcome);
+        }
     }
 
-    pub fn task_remove(&mut self, task_index: usize) -> Task {
-        self.tasks.remove(task_index)
+    /// Focus sessions are attached to the active task and carry the
+    /// interruptions and idle time logged while they ran.
+    fn session_record(&mut self, mode: TimerType, duration: Duration, outcome: SessionOutcome) {
+        self.session_record_at(mode, duration, outcome, Local::now());
     }
 
-    pub fn task_remove_by_attributes(&mut self, task: Task) {
-        for (idx, t) in self.tasks.iter().enumerate() {
-            if t == &task {
-                self.tasks.remove(idx);
-                break;
-            }
+    fn session_record_at(
+        &mut self,
+        mode: TimerType,
+        duration: Duration,
+        outcome: SessionOutcome,
+        ended_at: DateTime<Local>,
+    ) {
+        let event = match mode {
+            TimerType::Focus => HookEvent::FocusEnd,
+            _ => HookEvent::BreakEnd,
+        };
+        let mut data = HookData::new(event, self.task_active_get()).phase(duration, Some(outcome));
+        data.at = ended_at;
+        self.hooks.run(data);
+
+        let mut session = Session::new(mode, duration, outcome);
+        session.ended_at = ended_at;
+        if session.mode == TimerType::Focus {
+            session.task = self.active_task;
+            session.interruptions = std::mem::take(&mut self.interruptions);
+            session.idle = std::mem::take(&mut self.idle);
         }
+        self.history.push(session);
+        self.goal_cache.set(None);
+        self.stats_cache.take();
     }
 
-    pub fn task_complete(&mut self, task_index: usize) {
-        for (i, task) in self.tasks.iter_mut().filter(|x| !x.completed).enumerate() {
-            if i == task_index {
-                task.completed = true;
-                break;
-            }
+    fn timer_state(&self) -> TimerState {
+        TimerState {
+            focus: self.focus,
+            rest: self.rest,
+            preset: self.preset.clone(),
+            focus_count: self.focus_count,
+            timer: self.timer.clone(),
+            interruptions: self.interruptions.clone(),
+            idle: self.idle.clone(),
         }
     }
 
-    pub fn task_not_complete(&mut self, task_index: usize) {
-        for (i, task) in self.tasks.iter_mut().filter(|x| x.completed).enumerate() {
-            if i == task_index {
-                task.completed = false;
-                break;
-            }
+    /// Runs a timer action as one undoable command, along with the sessions
+    /// it records.
+    fn timer_command(&mut self, description: String, action: impl FnOnce(&mut Self)) {
+        let before = self.timer_state();
+        let sessions = self.history.sessions.len();
+        action(self);
+        let after = self.timer_state();
+
+        if before != after {
+            self.changes.push(Change::Timer {
+                before: Box::new(before),
+                after: Box::new(after),
+            });
         }
+        let recorded = self.history.sessions[sessions..].to_vec();
+        self.changes
+            .extend(recorded.into_iter().map(Change::SessionPush));
+        self.command_commit(description);
     }
 
-    pub fn task_get_by_complete(&self, completed: bool) -> Vec<Task> {
-        let completed_tasks: Vec<Task> = self
-            .tasks
-            .clone()
-            .into_iter()
-            .filter(|task| task.completed == completed)
-            .collect();
+    fn change_do(&mut self, change: Change) {
+        self.change_apply(&change);
+        self.changes.push(change);
+    }
 
-        completed_tasks
+    fn change_apply(&mut self, change: &Change) {
+        if let Change::TaskInsert { .. } | Change::TaskRemove { .. } | Change::TaskReplace { .. } =
+            change
+        {
+            self.open_cache.take();
+        }
+
+        match change.clone() {
+            Change::TaskInsert { index, task } => {
+                self.tasks.insert(index.min(self.tasks.len()), task);
+            }
+            Change::TaskRemove { index, task } => {
+                if self.tasks.get(index) == Some(&task) {
+                    self.tasks.remove(index);
+                }
+            }
+            Change::TaskReplace { index, after, .. } => {
+                if let Some(task) = self.tasks.get_mut(index) {
+                    *task = *after;
+                }
+            }
+            Change::TaskSwap { a, b } => {
+                if a < self.tasks.len() && b < self.tasks.len() {
+                    self.tasks.swap(a, b);
+                }
+            }
+            Change::ArchiveInsert { index, task } => {
+                self.archive.insert(index.min(self.archive.len()), task);
+            }
+            Change::ArchiveRemove { index, task } => {
+                if self.archive.get(index) == Some(&task) {
+                    self.archive.remove(index);
+                }
+            }
+            Change::ActiveTask { after, .. } => self.active_task = after,
+            Change::Timer { after, .. } => {
+                self.focus = after.focus;
+                self.rest = after.rest;
+                self.preset = after.preset;
+                self.focus_count = after.focus_count;
+                self.timer = after.timer;
+                self.interruptions = after.interruptions;
+                self.idle = after.idle;
+            }
+            Change::SessionPush(session) => {
+                self.history.push(session);
+                self.goal_cache.set(None);
+                self.stats_cache.take();
+            }
+            Change::SessionRemove(session) => {
+                if let Some(index) = self.history.sessions.iter().rposition(|s| *s == session) {
+                    self.history.sessions.remove(index);
+                    self.goal_cache.set(None);
+                    self.stats_cache.take();
+                }
+            }
+        }
     }
 
-    pub fn to_string(&self) -> String {
-        let timer_type = self.timer.to_string();
+    /// Groups the changes made since the last command into one that can be
+    /// undone.
+    fn command_commit(&mut self, description: String) {
+        let changes = std::mem::take(&mut self.changes);
+        if !changes.is_empty() {
+            self.commands.push(Command {
+                description,
+                changes,
+            });
+            self.revision += 1;
+        }
+    }
 
-        let timer_string = self.get_current_timer().to_string();
-        format!("{timer_type}: \n\t {timer_string}")
+    /// Reverts the last command. Returns what it was, for status messages.
+    pub fn undo(&mut self) -> Option<String> {
+        let command = self.commands.undo()?;
+        self.revision += 1;
+        for change in command.inverse().changes.iter() {
+            self.change_apply(change);
+        }
+        Some(command.description)
     }
 
-    pub fn get_current_timer(&self) -> Timer {
-        match &self.timer {
-            TimerType::Focus => self.focus,
-            TimerType::Rest => self.rest,
-            TimerType::Transitioning(s) => match s.deref() {
-                TimerType::Focus => self.focus,
-                TimerType::Rest => self.rest,
-                _ => unimplemented!(),
-            },
+    /// Applies the last undone command again.
+    pub fn redo(&mut self) -> Option<String> {
+        let command = self.commands.redo()?;
+        self.revision += 1;
+        for change in command.changes.iter() {
+            self.change_apply(change);
         }
+        Some(command.description)
     }
 
-    // TODO: Add a extend mode option.
-}
+    pub fn get_mode(&self) -> TimerType {
+        self.timer.clone()
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    pub fn history_get(&self) -> &History {
+        &self.history
+    }
 
-    const FOCUS_TIME: Duration = Duration::from_secs(15);
-    const REST_TIME: Duration = Duration::from_secs(15);
+    pub fn goal_set(&mut self, goal: DailyGoal) {
+        self.goal = goal;
+        self.goal_cache.set(None);
+    }
 
-    mod new {
-        use super::*;
+    pub fn goal_get(&self) -> DailyGoal {
+        self.goal
+    }
 
-        #[test]
-        fn should_initialize_in_focus_time() {
-            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            assert_eq!(pomodoro.timer, TimerType::Focus);
-        }
+    pub fn goal_today(&self) -> DayFocus {
+        self.goal_cached().0
+    }
 
-        #[test]
-        fn should_initalize_timers() {
-            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+    pub fn goal_progress(&self) -> String {
+        self.goal.progress(&self.goal_today())
+    }
 
-            let focus_timer = Timer::new(FOCUS_TIME);
-            let rest_timer = Timer::new(REST_TIME);
+    pub fn streaks_get(&self) -> Streaks {
+        self.goal_cached().1
+    }
 
-            assert_eq!(pomodoro.focus, focus_timer);
-            assert_eq!(pomodoro.rest, rest_timer);
+    fn goal_cached(&self) -> (DayFocus, Streaks) {
+        let today = Local::now().date_naive();
+        if let Some((date, day, streaks)) = self.goal_cache.get() {
+            if date == today {
+                return (day, streaks);
+            }
         }
 
-        #[test]
-        fn should_initialize_with_the_play_sound_alarm_equals_true() {
-            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            assert_eq!(pomodoro.play_sound_alarm, true);
-        }
+        let day = self.history.focus_on(today);
+        let streaks = Streaks::compute(&self.history, &self.goal, today);
+        self.goal_cache.set(Some((today, day, streaks)));
+        (day, streaks)
+    }
 
-        #[test]
-        fn should_initialize_with_the_task_vec_empty() {
-            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            assert_eq!(pomodoro.tasks.is_empty(), true);
-        }
+    pub fn task_add(&mut self, new_task: Task) {
+        let description = format!("add \"{}\"", new_task.name);
+        self.task_insert(new_task);
+        self.command_commit(description);
     }
 
-    mod forward {
-        use super::*;
+    fn task_insert(&mut self, task: Task) {
+        let index = self.tasks.len();
+        self.change_do(Change::TaskInsert { index, task });
+    }
 
-        #[test]
-        fn should_advance_current_time_one_second_by_timer_type() {
-            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+    /// Every task, ignoring the filter and the priority sort.
+    pub fn tasks_get(&self) -> Vec<Task> {
+        self.tasks.clone()
+    }
 
-            pomodoro.timer = TimerType::Focus;
-            pomodoro.forward();
-            let new_focus_time = FOCUS_TIME - Duration::from_secs(1);
-            assert_eq!(pomodoro.focus.current_time, new_focus_time);
+    pub fn task_get(&self, task_id: u64) -> Option<Task> {
+        self.tasks.iter().find(|task| task.id == task_id).cloned()
+    }
 
-            pomodoro.timer = TimerType::Rest;
-            pomodoro.forward();
-            let new_rest_time = REST_TIME - Duration::from_secs(1);
-            assert_eq!(pomodoro.rest.current_time, new_rest_time);
-        }
+    /// Whether the task is completed and its index for the task methods, or
+    /// `None` when it isn't stored or the filter hides it.
+    pub fn task_position(&self, task_id: u64) -> Option<(bool, usize)> {
+        [false, true].into_iter().find_map(|completed| {
+            self.task_get_by_complete(completed)
+                .iter()
+                .position(|task| task.id == task_id)
+                .map(|index| (completed, index))
+        })
+    }
 
-        #[test]
-        fn should_reset_timer_when_is_done() {
-            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            // Disable alarm transition.
-            pomodoro.alarm_disable();
+    /// Replaces the stored task with the same id. Returns `false` if there
+    /// is no such task.
+    pub fn task_update(&mut self, task: Task) -> bool {
+        let index = match self.tasks.iter().position(|t| t.id == task.id) {
+            Some(index) => index,
+            None => return false,
+        };
 
-            pomodoro.focus.current_time = Duration::from_secs(1);
-            pomodoro.forward();
-            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+        let description = format!("edit \"{}\"", task.name);
+        self.task_replace(index, task);
+        self.command_commit(description);
+        true
+    }
 
-            assert_eq!(pomodoro.timer, TimerType::Rest);
-            pomodoro.rest.current_time = Duration::from_secs(1);
-            pomodoro.forward();
-            assert_eq!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
+    fn task_replace(&mut self, index: usize, task: Task) {
+        let before = self.tasks[index].clone();
+        if before != task {
+            self.change_do(Change::TaskReplace {
+                index,
+                before: Box::new(before),
+                after: Box::new(task),
+            });
         }
+    }
 
-        #[test]
-        fn should_exit_transition() {
-            std::env::set_var("ENV", "TEST");
-            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+    pub fn task_remove(&mut self, task_index: usize) -> Task {
+        let task = self.tasks[task_index].clone();
+        self.change_do(Change::TaskRemove {
+            index: task_index,
+            task: task.clone(),
+        });
+        if self.active_task == Some(task.id) {
+            self.active_set(None);
+        }
+        self.command_commit(format!("remove \"{}\"", task.name));
+        task
+    }
+
+    pub fn task_remove_by_attributes(&mut self, task: Task) {
+        if let Some(idx) = self.tasks.iter().position(|t| t == &task) {
+            self.task_remove(idx);
+        }
+    }
+
+    /// Indexes used by the task methods point into the lists returned by
+    /// `task_get_by_complete`, in the order they are shown.
+    fn task_id_at(&self, completed: bool, task_index: usize) -> Option<u64> {
+        self.task_get_by_complete(completed)
+            .get(task_index)
+            .map(|task| task.id)
+    }
+
+    /// Applies `edit` to a copy of the task at `task_index` and stores it
+    /// back. Returns the edited task.
+    fn task_edit(
+        &mut self,
+        completed: bool,
+        task_index: usize,
+        edit: impl FnOnce(&mut Task),
+    ) -> Option<Task> {
+        let task_id = self.task_id_at(completed, task_index)?;
+        let index = self.tasks.iter().position(|task| task.id == task_id)?;
+
+        let mut task = self.tasks[index].clone();
+        edit(&mut task);
+        self.task_replace(index, task.clone());
+        Some(task)
+    }
+
+    pub fn task_complete(&mut self, task_index: usize) {
+        if let Some(task) = self.task_edit(false, task_index, |task| task.complete_set(true)) {
+            self.task_completed(task.id);
+            self.command_commit(format!("complete \"{}\"", task.name));
+        }
+    }
+
+    /// Follow-up of a task being completed: it stops being the active one
+    /// and, if it recurs, its next occurrence is added.
+    fn task_completed(&mut self, task_id: u64) {
+        self.hooks
+            .run(HookData::new(HookEvent::TaskDone, self.task_get(task_id)));
+        if self.active_task == Some(task_id) {
+            self.active_set(None);
+        }
+
+        let today = Local::now().date_naive();
+        let Some(index) = self.tasks.iter().position(|task| task.id == task_id) else {
+            return;
+        };
+        if let Some(next) = self.tasks[index].next_occurrence(today) {
+            let mut task = self.tasks[index].clone();
+            task.recurred_as = Some(next.id);
+            self.task_replace(index, task);
+            self.task_insert(next);
+        }
+    }
+
+    /// Checks or unchecks a subtask of the task at `task_index`. With
+    /// `auto_complete_parent`, the task follows its subtasks: it completes
+    /// when the last one is checked and reopens when one is unchecked.
+    pub fn subtask_toggle(&mut self, completed: bool, task_index: usize, subtask_index: usize) {
+        let auto_complete_parent = self.auto_complete_parent;
+        let mut checked = None;
+        let task = self.task_edit(completed, task_index, |task| {
+            if let Some(subtask) = task.subtasks.get_mut(subtask_index) {
+                subtask.completed = !subtask.completed;
+                checked = Some((subtask.name.clone(), subtask.completed));
+            }
+            if checked.is_some() && auto_complete_parent {
+                task.complete_set(task.subtasks_completed());
+            }
+        });
+
+        let (task, (name, checked)) = match (task, checked) {
+            (Some(task), Some(checked)) => (task, checked),
+            _ => return,
+        };
+        if task.completed && !completed {
+            self.task_completed(task.id);
+        }
+        let action = if checked { "check" } else { "uncheck" };
+        self.command_commit(format!("{action} \"{name}\""));
+    }
+
+    pub fn subtask_remove(&mut self, completed: bool, task_index: usize, subtask_index: usize) {
+        let mut removed = None;
+        self.task_edit(completed, task_index, |task| {
+            if subtask_index < task.subtasks.len() {
+                removed = Some(task.subtasks.remove(subtask_index));
+            }
+        });
+
+        if let Some(subtask) = removed {
+            self.command_commit(format!("remove \"{}\"", subtask.name));
+        }
+    }
+
+    /// The commands to run on timer and task events.
+    pub fn hooks_set(&mut self, hooks: Hooks) {
+        self.hooks = hooks;
+    }
+
+    /// Waits for the hooks that are still running, before exiting.
+    pub fn hooks_wait(&self) {
+        self.hooks.wait();
+    }
+
+    fn hook_run(&self, event: HookEvent, duration: Duration, outcome: Option<SessionOutcome>) {
+        self.hooks
+            .run(HookData::new(event, self.task_active_get()).phase(duration, outcome));
+    }
+
+    pub fn auto_complete_parent_set(&mut self, auto_complete_parent: bool) {
+        self.auto_complete_parent = auto_complete_parent;
+    }
+
+    pub fn auto_complete_parent_get(&self) -> bool {
+        self.auto_complete_parent
+    }
+
+    /// Sets the not completed task at `task_index` as the one being worked
+    /// on. Activating the active task again clears it. Returns `false`,
+    /// leaving the active task alone, if the task is blocked.
+    pub fn task_activate(&mut self, task_index: usize) -> bool {
+        let task = match self.task_get_by_complete(false).get(task_index) {
+            Some(task) => task.clone(),
+            None => return true,
+        };
+
+        if self.active_task == Some(task.id) {
+            self.active_task = None;
+        } else if self.task_is_blocked(&task) {
+            return false;
+        } else {
+            self.active_task = Some(task.id);
+        }
+        true
+    }
+
+    /// The not completed tasks `task` is still waiting on. Completing or
+    /// removing them unblocks it.
+    pub fn task_blockers(&self, task: &Task) -> Vec<Task> {
+        self.tasks
+            .iter()
+            .filter(|t| !t.completed && task.blocked_by.contains(&t.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `task` waits on a not completed task, as `task_blockers`
+    /// but without going through every task, as the TUI asks every frame.
+    pub fn task_is_blocked(&self, task: &Task) -> bool {
+        let open = self.open_cache.get_or_init(|| {
+            self.tasks
+                .iter()
+                .filter(|task| !task.completed)
+                .map(|task| task.id)
+                .collect()
+        });
+        task.blocked_by.iter().any(|id| open.contains(id))
+    }
+
+    fn active_set(&mut self, active_task: Option<u64>) {
+        self.change_do(Change::ActiveTask {
+            before: self.active_task,
+            after: active_task,
+        });
+    }
+
+    pub fn task_estimate_set(&mut self, task_index: usize, estimate: Option<u32>) {
+        let estimate = estimate.map(|estimate| estimate.min(ESTIMATE_MAX));
+        if let Some(task) = self.task_edit(false, task_index, |task| task.estimate = estimate) {
+            self.command_commit(format!("estimate of \"{}\"", task.name));
+        }
+    }
+
+    pub fn task_priority_set(&mut self, task_index: usize, priority: Option<Priority>) {
+        if let Some(task) = self.task_edit(false, task_index, |task| task.priority = priority) {
+            self.command_commit(format!("priority of \"{}\"", task.name));
+        }
+    }
+
+    /// Swaps the task at `task_index` with its neighbour in the same list.
+    /// Returns the new index of the task, if it moved. Doesn't move anything
+    /// while sorted by priority, where the neighbours on screen aren't the
+    /// ones in the manual order.
+    pub fn task_move(&mut self, completed: bool, task_index: usize, up: bool) -> Option<usize> {
+        if self.sort_by_priority {
+            return None;
+        }
+
+        let target_index = if up {
+            task_index.checked_sub(1)?
+        } else {
+            task_index + 1
+        };
+
+        let task_id = self.task_id_at(completed, task_index)?;
+        let target_id = self.task_id_at(completed, target_index)?;
+        let a = self.tasks.iter().position(|task| task.id == task_id)?;
+        let b = self.tasks.iter().position(|task| task.id == target_id)?;
+        let name = self.tasks[a].name.clone();
+        self.change_do(Change::TaskSwap { a, b });
+        self.command_commit(format!("move \"{name}\""));
+
+        Some(target_index)
+    }
+
+    /// Moves every completed task to the archive. Returns how many moved.
+    pub fn archive_done(&mut self) -> usize {
+        let count = self.tasks_archive(|task| task.completed);
+        self.command_commit(format!("archive {count} done tasks"));
+        count
+    }
+
+    /// Archives the tasks completed `archive_after_days` or more days before
+    /// `today`. Returns how many moved.
+    pub fn archive_old(&mut self, today: NaiveDate) -> usize {
+        let days = match self.archive_after_days {
+            Some(days) => days as i64,
+            None => return 0,
+        };
+
+        let count = self.tasks_archive(|task| {
+            task.completed
+                && task
+                    .completed_at
+                    .is_some_and(|at| (today - at.date_naive()).num_days() >= days)
+        });
+        self.command_commit(format!("archive {count} old tasks"));
+        count
+    }
+
+    fn tasks_archive(&mut self, should_archive: impl Fn(&Task) -> bool) -> usize {
+        let mut count = 0;
+        let mut index = 0;
+        while index < self.tasks.len() {
+            if !should_archive(&self.tasks[index]) {
+                index += 1;
+                continue;
+            }
+
+            let task = self.tasks[index].clone();
+            self.change_do(Change::TaskRemove {
+                index,
+                task: task.clone(),
+            });
+            self.change_do(Change::ArchiveInsert {
+                index: self.archive.len(),
+                task,
+            });
+            count += 1;
+        }
+        count
+    }
+
+    /// `None` turns automatic archiving off.
+    pub fn archive_after_days_set(&mut self, days: Option<u32>) {
+        self.archive_after_days = days;
+    }
+
+    /// Archived tasks matching `query` in the name, description, project or
+    /// tags, best matches first. Ties, as with an empty query, show the
+    /// most recently completed first.
+    pub fn archive_search(&self, query: &str) -> Vec<Task> {
+        let mut matches: Vec<(u32, &Task)> = self
+            .archive
+            .iter()
+            .filter_map(|task| {
+                let fields = [&task.name, &task.description]
+                    .into_iter()
+                    .chain(task.project.iter())
+                    .chain(task.tags.iter());
+                let score = fields.filter_map(|field| fuzzy_score(query, field)).max()?;
+                Some((score, task))
+            })
+            .collect();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(b.completed_at.cmp(&a.completed_at))
+        });
+        matches.into_iter().map(|(_, task)| task.clone()).collect()
+    }
+
+    /// Brings an archived task back to the TODO list. Returns `false` if
+    /// there is no such task in the archive.
+    pub fn archive_restore(&mut self, task_id: u64) -> bool {
+        let index = match self.archive.iter().position(|task| task.id == task_id) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let task = self.archive[index].clone();
+        self.change_do(Change::ArchiveRemove {
+            index,
+            task: task.clone(),
+        });
+        let mut restored = task;
+        restored.complete_set(false);
+        let description = format!("restore \"{}\"", restored.name);
+        self.task_insert(restored);
+        self.command_commit(description);
+        true
+    }
+
+    pub fn sort_by_priority_toggle(&mut self) {
+        self.sort_by_priority = !self.sort_by_priority;
+    }
+
+    pub fn sort_by_priority_get(&self) -> bool {
+        self.sort_by_priority
+    }
+
+    /// Only shows tasks matching `filter` in the task lists.
+    pub fn filter_set(&mut self, filter: Option<TaskFilter>) {
+        self.filter = filter;
+    }
+
+    pub fn filter_get(&self) -> Option<TaskFilter> {
+        self.filter.clone()
+    }
+
+    pub fn projects_get(&self) -> Vec<String> {
+        let mut projects: Vec<String> = self
+            .tasks
+            .iter()
+            .filter_map(|task| task.project.clone())
+            .collect();
+        projects.sort();
+        projects.dedup();
+        projects
+    }
+
+    pub fn tags_get(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .tasks
+            .iter()
+            .flat_map(|task| task.tags.clone())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    pub fn task_active_get(&self) -> Option<Task> {
+        let active_id = self.active_task?;
+        self.tasks.iter().find(|task| task.id == active_id).cloned()
+    }
+
+    pub fn task_stats(&self) -> &HashMap<u64, TaskStats> {
+        self.stats_cache
+            .get_or_init(|| self.history.focus_by_task())
+    }
+
+    pub fn report(&self) -> Report {
+        let tasks: Vec<Task> = self
+            .tasks
+            .iter()
+            .chain(self.archive.iter())
+            .cloned()
+            .collect();
+        Report::new(&tasks, &self.history)
+    }
+
+    pub fn task_not_complete(&mut self, task_index: usize) {
+        if let Some(task) = self.task_edit(true, task_index, |task| task.complete_set(false)) {
+            self.command_commit(format!("reopen \"{}\"", task.name));
+        }
+    }
+
+    pub fn task_get_by_complete(&self, completed: bool) -> Vec<Task> {
+        let mut completed_tasks: Vec<Task> = self
+            .tasks
+            .clone()
+            .into_iter()
+            .filter(|task| task.completed == completed)
+            .filter(|task| match &self.filter {
+                Some(filter) => filter.matches(task),
+                None => true,
+            })
+            .collect();
+
+        if self.sort_by_priority {
+            // Stable, so tasks with the same priority keep the manual order.
+            completed_tasks.sort_by_key(|task| (task.priority.is_none(), task.priority));
+        }
+
+        completed_tasks
+    }
+
+    pub fn to_string(&self) -> String {
+        let timer_type = self.timer.to_string();
+
+        let timer_string = self.get_current_timer().to_string();
+        format!("{timer_type}: \n\t {timer_string}")
+    }
+
+    pub fn get_current_timer(&self) -> Timer {
+        match &self.timer {
+            TimerType::Focus => self.focus,
+            TimerType::Rest => self.rest,
+            TimerType::Transitioning(s) => match s.deref() {
+                TimerType::Focus => self.focus,
+                TimerType::Rest => self.rest,
+                _ => unimplemented!(),
+            },
+        }
+    }
+
+    // TODO: Add a extend mode option.
+}
+
+/// Plays the sound at `path` once, blocking until it ends.
+pub fn sound_play(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (_stream, stream_handle) = OutputStream::try_default()?;
+    let file = std::fs::File::open(path)?;
+    let beep = stream_handle.play_once(BufReader::new(file))?;
+    beep.sleep_until_end();
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FOCUS_TIME: Duration = Duration::from_secs(15);
+    const REST_TIME: Duration = Duration::from_secs(15);
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn should_initialize_in_focus_time() {
+            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+        }
+
+        #[test]
+        fn should_initalize_timers() {
+            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            let focus_timer = Timer::new(FOCUS_TIME);
+            let rest_timer = Timer::new(REST_TIME);
+
+            assert_eq!(pomodoro.focus, focus_timer);
+            assert_eq!(pomodoro.rest, rest_timer);
+        }
+
+        #[test]
+        fn should_initialize_with_the_play_sound_alarm_equals_true() {
+            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            assert_eq!(pomodoro.play_sound_alarm, true);
+        }
+
+        #[test]
+        fn should_initialize_with_the_task_vec_empty() {
+            let pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            assert_eq!(pomodoro.tasks.is_empty(), true);
+        }
+    }
+
+    mod forward {
+        use super::*;
+
+        #[test]
+        fn should_advance_current_time_one_second_by_timer_type() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.timer = TimerType::Focus;
+            pomodoro.forward();
+            let new_focus_time = FOCUS_TIME - Duration::from_secs(1);
+            assert_eq!(pomodoro.focus.current_time, new_focus_time);
+
+            pomodoro.timer = TimerType::Rest;
+            pomodoro.forward();
+            let new_rest_time = REST_TIME - Duration::from_secs(1);
+            assert_eq!(pomodoro.rest.current_time, new_rest_time);
+        }
+
+        #[test]
+        fn should_end_the_phase_with_less_than_a_second_left() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+
+            pomodoro.focus.current_time = Duration::from_millis(400);
+            pomodoro.forward();
+
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+        }
+
+        #[test]
+        fn should_reset_timer_when_is_done() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            // Disable alarm transition.
+            pomodoro.alarm_disable();
+
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+            pomodoro.rest.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+            assert_eq!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
+        }
+
+        #[test]
+        fn should_exit_transition() {
+            std::env::set_var("ENV", "TEST");
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
 
             pomodoro.timer = TimerType::Transitioning(Box::new(TimerType::Focus));
             pomodoro.forward();
@@ -281,94 +1387,838 @@ mod test {
         }
 
         #[test]
-        fn should_not_transition_if_timer_is_disabled() {
+        fn should_not_transition_if_timer_is_disabled() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+
+            pomodoro.rest.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+        }
+
+        #[should_panic]
+        #[test]
+        fn should_crash_if_is_transitioning_to_anoter_transition() {
+            std::env::set_var("ENV", "TEST");
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.timer = TimerType::Transitioning(Box::new(TimerType::Transitioning(
+                Box::new(TimerType::Focus),
+            )));
+            pomodoro.forward();
+        }
+    }
+
+    mod long_rest_set {
+        use super::*;
+
+        const LONG_REST_TIME: Duration = Duration::from_secs(30);
+
+        fn focus_complete(pomodoro: &mut Pomodoro) {
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+        }
+
+        #[test]
+        fn should_take_a_long_rest_every_few_focus_blocks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+            pomodoro.long_rest_set(LONG_REST_TIME, 2);
+
+            focus_complete(&mut pomodoro);
+            assert_eq!(pomodoro.rest.initial_time, REST_TIME);
+            assert!(!pomodoro.rest_is_long());
+            pomodoro.next_mode();
+
+            focus_complete(&mut pomodoro);
+            assert_eq!(pomodoro.rest.initial_time, LONG_REST_TIME);
+            assert_eq!(pomodoro.rest.current_time, LONG_REST_TIME);
+            assert!(pomodoro.rest_is_long());
+            pomodoro.next_mode();
+
+            focus_complete(&mut pomodoro);
+            assert_eq!(pomodoro.rest.initial_time, REST_TIME);
+        }
+
+        #[test]
+        fn should_not_count_skipped_focus_blocks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.long_rest_set(LONG_REST_TIME, 1);
+
+            pomodoro.next_mode();
+            assert_eq!(pomodoro.rest.initial_time, REST_TIME);
+
+            pomodoro.undo();
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+        }
+
+        #[test]
+        fn should_keep_rests_short_when_off() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+            pomodoro.long_rest_set(LONG_REST_TIME, 0);
+
+            for _ in 0..3 {
+                focus_complete(&mut pomodoro);
+                assert_eq!(pomodoro.rest.initial_time, REST_TIME);
+                pomodoro.next_mode();
+            }
+        }
+    }
+
+    mod preset_apply {
+        use super::*;
+
+        fn pomodoro() -> Pomodoro {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.presets_set(vec![Preset::new("deep", FOCUS_TIME * 2, REST_TIME * 2)]);
+            pomodoro.focus.current_time = Duration::from_secs(5);
+            pomodoro
+        }
+
+        #[test]
+        fn should_rebuild_the_timers() {
+            let mut pomodoro = pomodoro();
+
+            assert!(pomodoro.preset_apply("deep", PhaseChange::Keep));
+
+            assert_eq!(pomodoro.preset_get().name, "deep");
+            assert_eq!(pomodoro.focus.initial_time, FOCUS_TIME * 2);
+            assert_eq!(pomodoro.focus.current_time, Duration::from_secs(5));
+            assert_eq!(pomodoro.rest, Timer::new(REST_TIME * 2));
+        }
+
+        #[test]
+        fn should_change_the_running_phase_as_asked() {
+            let mut scaled = pomodoro();
+            scaled.preset_apply("deep", PhaseChange::Scale);
+            assert_eq!(scaled.focus.current_time, Duration::from_secs(10));
+
+            let mut restarted = pomodoro();
+            restarted.preset_apply("deep", PhaseChange::Restart);
+            assert_eq!(restarted.focus, Timer::new(FOCUS_TIME * 2));
+        }
+
+        #[test]
+        fn should_record_the_time_the_block_ran() {
+            let mut pomodoro = pomodoro();
+            pomodoro.alarm_disable();
+            pomodoro.preset_apply("deep", PhaseChange::Keep);
+
+            for _ in 0..5 {
+                pomodoro.forward();
+            }
+
+            let sessions = &pomodoro.history_get().sessions;
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].duration, FOCUS_TIME);
+            assert_eq!(pomodoro.focus, Timer::new(FOCUS_TIME * 2));
+        }
+
+        #[test]
+        fn should_ignore_unknown_presets() {
+            let mut pomodoro = pomodoro();
+
+            assert!(!pomodoro.preset_apply("email", PhaseChange::Keep));
+            assert_eq!(pomodoro.preset_get().name, DEFAULT_PRESET);
+        }
+
+        #[test]
+        fn should_be_undone() {
+            let mut pomodoro = pomodoro();
+            pomodoro.preset_apply("deep", PhaseChange::Restart);
+
+            assert_eq!(pomodoro.undo(), Some("preset deep".to_string()));
+            assert_eq!(pomodoro.preset_get().name, DEFAULT_PRESET);
+            assert_eq!(pomodoro.focus.initial_time, FOCUS_TIME);
+            assert_eq!(pomodoro.focus.current_time, Duration::from_secs(5));
+        }
+    }
+
+    mod alarm_disable {
+        use super::*;
+
+        #[test]
+        fn should_set_play_sound_alarm_to_false() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            assert_eq!(pomodoro.play_sound_alarm, true);
+
+            pomodoro.alarm_disable();
+            assert_eq!(pomodoro.play_sound_alarm, false);
+        }
+    }
+
+    mod next_mode {
+        use super::*;
+
+        #[test]
+        fn should_move_to_another_timer() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+            pomodoro.next_mode();
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+            pomodoro.next_mode();
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+        }
+
+        #[should_panic]
+        #[test]
+        fn should_crash_if_in_transitioning() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.timer = TimerType::Transitioning(Box::new(TimerType::Focus));
+            pomodoro.next_mode();
+        }
+    }
+
+    mod reset_timer {
+        use super::*;
+
+        #[test]
+        fn should_reset_by_the_timer_type() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.forward();
+            assert_ne!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+            pomodoro.reset_timer(TimerType::Focus);
+            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+
+            pomodoro.next_mode();
+            assert_eq!(pomodoro.timer, TimerType::Rest);
+            pomodoro.forward();
+            assert_ne!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
+            pomodoro.reset_timer(TimerType::Rest);
+            assert_eq!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
+        }
+
+        #[should_panic]
+        #[test]
+        fn should_crash_if_is_resetting_a_transition() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.reset_timer(TimerType::Transitioning(Box::new(TimerType::Focus)));
+        }
+    }
+
+    mod archive {
+        use super::*;
+        use chrono::Days;
+
+        fn pomodoro_with_done(names: &[&str]) -> Pomodoro {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::new("Todo", ""));
+            for name in names {
+                let mut task = Task::from_str(name);
+                task.complete_set(true);
+                pomodoro.task_add(task);
+            }
+            pomodoro
+        }
+
+        #[test]
+        fn should_archive_all_done_tasks_and_undo_it() {
+            let mut pomodoro = pomodoro_with_done(&["A", "B"]);
+            let tasks = pomodoro.tasks.clone();
+
+            assert_eq!(pomodoro.archive_done(), 2);
+            assert_eq!(pomodoro.tasks.len(), 1);
+            assert_eq!(pomodoro.archive_search("").len(), 2);
+
+            pomodoro.undo();
+            assert_eq!(pomodoro.tasks, tasks);
+            assert!(pomodoro.archive.is_empty());
+        }
+
+        #[test]
+        fn should_archive_tasks_done_for_a_while() {
+            let mut pomodoro = pomodoro_with_done(&["Old", "Recent"]);
+            let today = Local::now().date_naive();
+            pomodoro.tasks[1].completed_at = Some(Local::now() - Days::new(8));
+
+            assert_eq!(pomodoro.archive_old(today), 1);
+            assert_eq!(pomodoro.archive[0].name, "Old");
+
+            pomodoro.archive_after_days_set(None);
+            assert_eq!(pomodoro.archive_old(today + Days::new(30)), 0);
+        }
+
+        #[test]
+        fn should_search_and_restore_archived_tasks() {
+            let mut pomodoro =
+                pomodoro_with_done(&["Weekly report +work", "Read paper", "Groceries @errand"]);
+            pomodoro.archive_done();
+
+            let names: Vec<String> = pomodoro
+                .archive_search("rep")
+                .into_iter()
+                .map(|task| task.name)
+                .collect();
+            assert_eq!(names, vec!["Weekly report", "Read paper"]);
+            let errand = pomodoro.archive_search("errand");
+            assert_eq!(errand.len(), 1);
+
+            assert!(pomodoro.archive_restore(errand[0].id));
+            assert!(!pomodoro.archive_restore(errand[0].id));
+            let restored = pomodoro.task_get_by_complete(false);
+            assert_eq!(restored[1].name, "Groceries");
+            assert_eq!(pomodoro.archive.len(), 2);
+        }
+    }
+
+    mod undo {
+        use super::*;
+
+        #[test]
+        fn should_restore_a_removed_task_in_place() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::new("First", ""));
+            pomodoro.task_add(Task::new("Second", ""));
+            pomodoro.task_activate(0);
+            let tasks = pomodoro.tasks.clone();
+
+            pomodoro.task_remove(0);
+            assert_eq!(pomodoro.undo(), Some("remove \"First\"".to_string()));
+
+            assert_eq!(pomodoro.tasks, tasks);
+            assert_eq!(pomodoro.task_active_get(), Some(tasks[0].clone()));
+        }
+
+        #[test]
+        fn should_undo_a_completion_with_its_next_occurrence() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::from_str("Inbox zero rec:daily"));
+            let tasks = pomodoro.tasks.clone();
+
+            pomodoro.task_complete(0);
+            assert_eq!(pomodoro.tasks.len(), 2);
+            pomodoro.undo();
+
+            assert_eq!(pomodoro.tasks, tasks);
+        }
+
+        #[test]
+        fn should_undo_a_skip_with_its_session() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.focus.current_time = FOCUS_TIME - Duration::from_secs(5);
+
+            pomodoro.next_mode();
+            assert_eq!(pomodoro.history_get().sessions.len(), 1);
+            assert_eq!(pomodoro.undo(), Some("skip focus".to_string()));
+
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+            assert_eq!(
+                pomodoro.focus.current_time,
+                FOCUS_TIME - Duration::from_secs(5)
+            );
+            assert!(pomodoro.history_get().sessions.is_empty());
+        }
+
+        #[test]
+        fn should_redo_undone_commands() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for name in ["A", "B"] {
+                pomodoro.task_add(Task::new(name, ""));
+            }
+            pomodoro.task_move(false, 0, false);
+            let moved = pomodoro.tasks.clone();
+
+            pomodoro.undo();
+            assert_eq!(pomodoro.tasks[0].name, "A");
+            assert_eq!(pomodoro.redo(), Some("move \"A\"".to_string()));
+            assert_eq!(pomodoro.tasks, moved);
+            assert_eq!(pomodoro.redo(), None);
+        }
+    }
+
+    mod history {
+        use super::*;
+
+        #[test]
+        fn should_record_completed_blocks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+
+            let sessions = &pomodoro.history_get().sessions;
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].mode, TimerType::Focus);
+            assert_eq!(sessions[0].duration, FOCUS_TIME);
+            assert_eq!(sessions[0].outcome, SessionOutcome::Completed);
+            assert_eq!(pomodoro.goal_today().pomodoros, 1);
+        }
+
+        #[test]
+        fn should_update_the_goal_once_a_block_is_recorded() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+            pomodoro.goal_set(DailyGoal::Pomodoros(1));
+            assert_eq!(pomodoro.goal_today().pomodoros, 0);
+            assert_eq!(pomodoro.streaks_get().current, 0);
+
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+
+            assert_eq!(pomodoro.goal_today().pomodoros, 1);
+            assert_eq!(pomodoro.streaks_get().current, 1);
+            pomodoro.goal_set(DailyGoal::Pomodoros(2));
+            assert_eq!(pomodoro.streaks_get().current, 0);
+        }
+
+        #[test]
+        fn should_not_count_skipped_or_reset_blocks_toward_the_goal() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.forward();
+            pomodoro.reset_timer(TimerType::Focus);
+            pomodoro.forward();
+            pomodoro.next_mode();
+
+            let outcomes: Vec<SessionOutcome> = pomodoro
+                .history_get()
+                .sessions
+                .iter()
+                .map(|s| s.outcome)
+                .collect();
+            assert_eq!(
+                outcomes,
+                vec![SessionOutcome::Reset, SessionOutcome::Skipped]
+            );
+            assert_eq!(pomodoro.goal_today().pomodoros, 0);
+        }
+
+        #[test]
+        fn should_not_record_untouched_blocks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.next_mode();
+            pomodoro.reset_timer(TimerType::Rest);
+
+            assert!(pomodoro.history_get().sessions.is_empty());
+        }
+    }
+
+    mod interruption_add {
+        use super::*;
+
+        #[test]
+        fn should_attach_interruptions_to_the_focus_session() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+
+            assert!(pomodoro.interruption_add(InterruptionKind::Internal, None));
+            assert!(pomodoro
+                .interruption_add(InterruptionKind::External, Some("Phone call".to_string())));
+            assert_eq!(pomodoro.interruptions_current().internal, 1);
+            assert_eq!(pomodoro.interruptions_current().external, 1);
+
+            pomodoro.focus.current_time = Duration::from_secs(1);
+            pomodoro.forward();
+
+            let session = &pomodoro.history_get().sessions[0];
+            assert_eq!(session.interruptions.len(), 2);
+            assert_eq!(
+                session.interruptions[1].note,
+                Some("Phone call".to_string())
+            );
+            assert_eq!(pomodoro.interruptions_current().external, 0);
+        }
+
+        #[test]
+        fn should_not_log_interruptions_outside_of_focus() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.next_mode();
+
+            assert!(!pomodoro.interruption_add(InterruptionKind::Internal, None));
+            assert_eq!(pomodoro.interruptions_current().internal, 0);
+        }
+    }
+
+    mod void_current {
+        use super::*;
+
+        #[test]
+        fn should_restart_focus_without_counting_toward_the_goal() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            pomodoro.forward();
+            pomodoro.interruption_add(InterruptionKind::External, None);
+            pomodoro.void_current();
+
+            assert_eq!(pomodoro.timer, TimerType::Focus);
+            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+            let session = &pomodoro.history_get().sessions[0];
+            assert_eq!(session.outcome, SessionOutcome::Voided);
+            assert_eq!(session.interruptions.len(), 1);
+            assert_eq!(pomodoro.goal_today().pomodoros, 0);
+        }
+    }
+
+    mod task_stats {
+        use super::*;
+
+        #[test]
+        fn should_follow_the_sessions_recorded_and_undone() {
+            let task = Task::new("Name1", "Description1");
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(task.clone());
+            pomodoro.task_activate(0);
+            assert!(pomodoro.task_stats().is_empty());
+
+            for _ in 0..5 {
+                pomodoro.forward();
+            }
+            pomodoro.next_mode();
+            assert_eq!(
+                pomodoro.task_stats()[&task.id].focus,
+                Duration::from_secs(5)
+            );
+
+            pomodoro.undo();
+            assert!(pomodoro.task_stats().is_empty());
+        }
+    }
+
+    mod task_activate {
+        use super::*;
+
+        #[test]
+        fn should_attach_focus_sessions_to_the_active_task() {
+            let task = Task::new("Name1", "Description1");
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
             pomodoro.alarm_disable();
+            pomodoro.task_add(task.clone());
+
+            pomodoro.task_activate(0);
+            assert_eq!(pomodoro.task_active_get(), Some(task.clone()));
 
             pomodoro.focus.current_time = Duration::from_secs(1);
             pomodoro.forward();
-            assert_eq!(pomodoro.timer, TimerType::Rest);
-
             pomodoro.rest.current_time = Duration::from_secs(1);
             pomodoro.forward();
-            assert_eq!(pomodoro.timer, TimerType::Focus);
+
+            let sessions = &pomodoro.history_get().sessions;
+            assert_eq!(sessions[0].task, Some(task.id));
+            assert_eq!(sessions[1].task, None);
+
+            let stats = pomodoro.task_stats();
+            assert_eq!(stats[&task.id].pomodoros, 1);
+            assert_eq!(stats[&task.id].focus, FOCUS_TIME);
         }
 
-        #[should_panic]
         #[test]
-        fn should_crash_if_is_transitioning_to_anoter_transition() {
-            std::env::set_var("ENV", "TEST");
+        fn should_toggle_the_active_task() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::new("Name1", "Description1"));
 
-            pomodoro.timer = TimerType::Transitioning(Box::new(TimerType::Transitioning(
-                Box::new(TimerType::Focus),
-            )));
-            pomodoro.forward();
+            pomodoro.task_activate(0);
+            assert!(pomodoro.task_active_get().is_some());
+            pomodoro.task_activate(0);
+            assert!(pomodoro.task_active_get().is_none());
+        }
+
+        #[test]
+        fn should_clear_the_active_task_when_completed() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::new("Name1", "Description1"));
+
+            pomodoro.task_activate(0);
+            pomodoro.task_complete(0);
+            assert!(pomodoro.task_active_get().is_none());
         }
     }
 
-    mod alarm_disable {
+    mod task_estimate_set {
         use super::*;
 
         #[test]
-        fn should_set_play_sound_alarm_to_false() {
+        fn should_set_the_estimate_of_a_not_completed_task() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            assert_eq!(pomodoro.play_sound_alarm, true);
+            let mut completed_task = Task::new("Name1", "Description1");
+            completed_task.completed = true;
+            pomodoro.task_add(completed_task);
+            pomodoro.task_add(Task::new("Name2", "Description2"));
 
-            pomodoro.alarm_disable();
-            assert_eq!(pomodoro.play_sound_alarm, false);
+            pomodoro.task_estimate_set(0, Some(4));
+
+            assert_eq!(pomodoro.tasks[0].estimate, None);
+            assert_eq!(pomodoro.tasks[1].estimate, Some(4));
+
+            pomodoro.task_estimate_set(0, Some(u32::MAX));
+            assert_eq!(pomodoro.tasks[1].estimate, Some(ESTIMATE_MAX));
         }
     }
 
-    mod next_mode {
+    mod task_position {
         use super::*;
 
         #[test]
-        fn should_move_to_another_timer() {
+        fn should_find_the_list_and_index_of_a_task() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let first = Task::new("First", "");
+            let second = Task::new("Second", "");
+            pomodoro.task_add(first.clone());
+            pomodoro.task_add(second.clone());
+            pomodoro.task_complete(0);
+
+            assert_eq!(pomodoro.task_position(second.id), Some((false, 0)));
+            assert_eq!(pomodoro.task_position(first.id), Some((true, 0)));
+            assert_eq!(pomodoro.task_position(u64::MAX), None);
+        }
+    }
 
-            assert_eq!(pomodoro.timer, TimerType::Focus);
-            pomodoro.next_mode();
-            assert_eq!(pomodoro.timer, TimerType::Rest);
-            pomodoro.next_mode();
-            assert_eq!(pomodoro.timer, TimerType::Focus);
+    mod task_update {
+        use super::*;
+
+        #[test]
+        fn should_replace_the_task_in_place() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::new("Name1", "Description1"));
+            let task = Task::new("Nmae2", "Description2");
+            pomodoro.task_add(task.clone());
+            pomodoro.task_add(Task::new("Name3", "Description3"));
+
+            let mut edited = task.clone();
+            edited.name = "Name2".to_string();
+
+            assert!(pomodoro.task_update(edited.clone()));
+            assert_eq!(pomodoro.tasks[1], edited);
+            assert_eq!(pomodoro.task_get(task.id), Some(edited));
         }
 
-        #[should_panic]
         #[test]
-        fn should_crash_if_in_transitioning() {
+        fn should_not_add_unknown_tasks() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
-            pomodoro.timer = TimerType::Transitioning(Box::new(TimerType::Focus));
-            pomodoro.next_mode();
+
+            assert!(!pomodoro.task_update(Task::new("Name1", "Description1")));
+            assert!(pomodoro.tasks.is_empty());
         }
     }
 
-    mod reset_timer {
+    mod task_move {
         use super::*;
 
+        fn pomodoro_with_tasks(names: &[&str]) -> Pomodoro {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for name in names {
+                pomodoro.task_add(Task::from_str(name));
+            }
+            pomodoro
+        }
+
+        fn names(pomodoro: &Pomodoro) -> Vec<String> {
+            pomodoro
+                .task_get_by_complete(false)
+                .into_iter()
+                .map(|task| task.name)
+                .collect()
+        }
+
         #[test]
-        fn should_reset_by_the_timer_type() {
+        fn should_swap_with_the_neighbour() {
+            let mut pomodoro = pomodoro_with_tasks(&["A", "B", "C"]);
+
+            assert_eq!(pomodoro.task_move(false, 1, true), Some(0));
+            assert_eq!(names(&pomodoro), vec!["B", "A", "C"]);
+            assert_eq!(pomodoro.task_move(false, 1, false), Some(2));
+            assert_eq!(names(&pomodoro), vec!["B", "C", "A"]);
+        }
+
+        #[test]
+        fn should_not_move_past_the_ends() {
+            let mut pomodoro = pomodoro_with_tasks(&["A", "B"]);
+
+            assert_eq!(pomodoro.task_move(false, 0, true), None);
+            assert_eq!(pomodoro.task_move(false, 1, false), None);
+            assert_eq!(names(&pomodoro), vec!["A", "B"]);
+        }
+
+        #[test]
+        fn should_only_move_among_tasks_of_the_same_list() {
+            let mut pomodoro = pomodoro_with_tasks(&["A", "Done", "B"]);
+            pomodoro.tasks[1].completed = true;
+
+            assert_eq!(pomodoro.task_move(false, 1, true), Some(0));
+            assert_eq!(names(&pomodoro), vec!["B", "A"]);
+            assert_eq!(pomodoro.tasks[1].name, "Done");
+        }
+
+        #[test]
+        fn should_not_move_while_sorted_by_priority() {
+            let mut pomodoro = pomodoro_with_tasks(&["A", "B !1", "C"]);
+            pomodoro.sort_by_priority_toggle();
+
+            assert_eq!(pomodoro.task_move(false, 1, true), None);
+            assert_eq!(pomodoro.task_move(false, 1, false), None);
+            assert_eq!(names(&pomodoro), vec!["B", "A", "C"]);
+
+            pomodoro.sort_by_priority_toggle();
+            assert_eq!(names(&pomodoro), vec!["A", "B", "C"]);
+        }
+    }
+
+    mod sort_by_priority {
+        use super::*;
+
+        #[test]
+        fn should_show_higher_priorities_first() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for name in ["A", "B !3", "C !1", "D !3"] {
+                pomodoro.task_add(Task::from_str(name));
+            }
 
-            pomodoro.forward();
-            assert_ne!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
-            pomodoro.reset_timer(TimerType::Focus);
-            assert_eq!(pomodoro.focus.current_time, pomodoro.focus.initial_time);
+            pomodoro.sort_by_priority_toggle();
+            let names: Vec<String> = pomodoro
+                .task_get_by_complete(false)
+                .into_iter()
+                .map(|task| task.name)
+                .collect();
+            assert_eq!(names, vec!["C", "B", "D", "A"]);
 
-            pomodoro.next_mode();
-            assert_eq!(pomodoro.timer, TimerType::Rest);
-            pomodoro.forward();
-            assert_ne!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
-            pomodoro.reset_timer(TimerType::Rest);
-            assert_eq!(pomodoro.rest.current_time, pomodoro.rest.initial_time);
+            pomodoro.task_complete(0);
+            assert!(pomodoro.tasks[2].completed);
         }
+    }
+
+    mod task_blockers {
+        use super::*;
 
-        #[should_panic]
         #[test]
-        fn should_crash_if_is_resetting_a_transition() {
+        fn should_unblock_when_the_blockers_are_completed() {
             let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let blocker = Task::new("Write spec", "");
+            let mut blocked = Task::new("Implement", "");
+            blocked.blocked_by = vec![blocker.id];
+            pomodoro.task_add(blocker.clone());
+            pomodoro.task_add(blocked.clone());
 
-            pomodoro.reset_timer(TimerType::Transitioning(Box::new(TimerType::Focus)));
+            assert_eq!(pomodoro.task_blockers(&blocked), vec![blocker]);
+            assert!(!pomodoro.task_activate(1));
+            assert_eq!(pomodoro.task_active_get(), None);
+
+            pomodoro.task_complete(0);
+
+            assert!(!pomodoro.task_is_blocked(&blocked));
+            assert!(pomodoro.task_activate(0));
+            assert_eq!(pomodoro.task_active_get(), Some(blocked));
+        }
+    }
+
+    mod task_complete {
+        use super::*;
+
+        #[test]
+        fn should_add_the_next_occurrence_of_recurring_tasks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.task_add(Task::from_str("Inbox zero rec:daily"));
+            pomodoro.task_add(Task::from_str("Once"));
+
+            pomodoro.task_complete(0);
+            pomodoro.task_complete(0);
+
+            let not_completed = pomodoro.task_get_by_complete(false);
+            assert_eq!(pomodoro.task_get_by_complete(true).len(), 2);
+            assert_eq!(not_completed.len(), 1);
+            assert_eq!(not_completed[0].name, "Inbox zero");
+            assert_eq!(not_completed[0].recurrence, Some(Recurrence::Daily));
+            assert!(not_completed[0].scheduled.is_some());
+        }
+
+        #[test]
+        fn should_add_the_next_occurrence_once() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let task = Task::from_str("Inbox zero rec:daily");
+            pomodoro.task_add(task.clone());
+
+            pomodoro.task_complete(0);
+            pomodoro.task_not_complete(0);
+            let (_, index) = pomodoro.task_position(task.id).unwrap();
+            pomodoro.task_complete(index);
+
+            assert_eq!(pomodoro.task_get_by_complete(false).len(), 1);
+            assert_eq!(pomodoro.task_get_by_complete(true).len(), 1);
+
+            pomodoro.undo();
+            pomodoro.undo();
+            pomodoro.undo();
+            assert_eq!(pomodoro.tasks_get().len(), 1);
+            pomodoro.task_complete(0);
+            assert_eq!(pomodoro.tasks_get().len(), 2);
+        }
+    }
+
+    mod subtask_toggle {
+        use super::*;
+
+        fn pomodoro_with_checklist() -> Pomodoro {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let mut task = Task::new("Review PR", "");
+            task.subtasks = vec![Subtask::new("Read diff"), Subtask::new("Run tests")];
+            pomodoro.task_add(task);
+            pomodoro
+        }
+
+        #[test]
+        fn should_complete_the_parent_with_its_last_subtask() {
+            let mut pomodoro = pomodoro_with_checklist();
+            pomodoro.task_activate(0);
+
+            pomodoro.subtask_toggle(false, 0, 0);
+            assert!(pomodoro.tasks[0].subtasks[0].completed);
+            assert!(!pomodoro.tasks[0].completed);
+
+            pomodoro.subtask_toggle(false, 0, 1);
+            assert!(pomodoro.tasks[0].completed);
+            assert!(pomodoro.task_active_get().is_none());
+
+            pomodoro.subtask_toggle(true, 0, 1);
+            assert!(!pomodoro.tasks[0].completed);
+        }
+
+        #[test]
+        fn should_leave_the_parent_alone_without_auto_complete() {
+            let mut pomodoro = pomodoro_with_checklist();
+            pomodoro.auto_complete_parent_set(false);
+
+            pomodoro.subtask_toggle(false, 0, 0);
+            pomodoro.subtask_toggle(false, 0, 1);
+
+            assert!(pomodoro.tasks[0].subtasks_completed());
+            assert!(!pomodoro.tasks[0].completed);
+        }
+    }
+
+    mod filter_set {
+        use super::*;
+
+        #[test]
+        fn should_only_show_and_index_matching_tasks() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for name in ["A +work", "B +home @errand", "C +work @errand"] {
+                pomodoro.task_add(Task::from_str(name));
+            }
+
+            pomodoro.filter_set(TaskFilter::parse("@errand"));
+            let names: Vec<String> = pomodoro
+                .task_get_by_complete(false)
+                .into_iter()
+                .map(|task| task.name)
+                .collect();
+            assert_eq!(names, vec!["B", "C"]);
+
+            pomodoro.task_complete(1);
+            assert!(pomodoro.tasks[2].completed);
+
+            pomodoro.filter_set(None);
+            assert_eq!(pomodoro.task_get_by_complete(false).len(), 2);
+            assert_eq!(pomodoro.projects_get(), vec!["home", "work"]);
+            assert_eq!(pomodoro.tags_get(), vec!["errand"]);
         }
     }
 
@@ -387,4 +2237,350 @@ mod test {
             assert_eq!(pomodoro.tasks.len(), 0);
         }
     }
+
+    mod revision {
+        use super::*;
+
+        #[test]
+        fn should_go_up_with_commands_undo_and_redo() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            assert_eq!(pomodoro.revision(), 0);
+
+            pomodoro.task_add(Task::new("First", ""));
+            assert_eq!(pomodoro.revision(), 1);
+            pomodoro.undo();
+            pomodoro.redo();
+            assert_eq!(pomodoro.revision(), 3);
+
+            pomodoro.filter_set(Some(TaskFilter::Today));
+            pomodoro.undo();
+            pomodoro.undo();
+            assert_eq!(pomodoro.revision(), 4);
+        }
+    }
+
+    mod resume {
+        use super::*;
+
+        fn snapshot_after(
+            pomodoro: &Pomodoro,
+            paused: bool,
+            secs: i64,
+        ) -> (Snapshot, DateTime<Local>) {
+            let snapshot = pomodoro.snapshot(paused);
+            let now = snapshot.saved_at + chrono::Duration::seconds(secs);
+            (snapshot, now)
+        }
+
+        #[test]
+        fn should_pick_up_where_it_was_saved() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let task = Task::new("First", "");
+            pomodoro.task_add(task.clone());
+            pomodoro.task_activate(0);
+            pomodoro.focus.current_time = Duration::from_secs(10);
+            pomodoro.focus_count = 2;
+            let (snapshot, now) = snapshot_after(&pomodoro, true, 60);
+
+            let mut resumed = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            resumed.task_add(task.clone());
+            assert!(resumed.resume(&snapshot, now));
+
+            assert_eq!(
+                resumed.get_current_timer().current_time,
+                Duration::from_secs(10)
+            );
+            assert_eq!(resumed.focus_count, 2);
+            assert_eq!(resumed.task_active_get(), Some(task));
+        }
+
+        #[test]
+        fn should_count_the_time_it_kept_running() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let (snapshot, now) = snapshot_after(&pomodoro, false, 4);
+
+            assert!(!pomodoro.resume(&snapshot, now));
+
+            assert_eq!(pomodoro.get_mode(), TimerType::Focus);
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(4)
+            );
+        }
+
+        #[test]
+        fn should_count_whole_seconds_only() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let snapshot = pomodoro.snapshot(false);
+            let now = snapshot.saved_at + chrono::Duration::milliseconds(2500);
+
+            assert!(!pomodoro.resume(&snapshot, now));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(2)
+            );
+
+            for _ in 0..13 {
+                pomodoro.forward();
+            }
+            assert_eq!(pomodoro.history_get().sessions.len(), 1);
+        }
+
+        #[test]
+        fn should_stop_after_the_phase_it_was_in() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let (snapshot, now) = snapshot_after(&pomodoro, false, 60 * 60 * 48);
+
+            assert!(pomodoro.resume(&snapshot, now));
+
+            assert_eq!(pomodoro.get_mode(), TimerType::Rest);
+            assert_eq!(pomodoro.get_current_timer(), Timer::new(REST_TIME));
+            let sessions = &pomodoro.history_get().sessions;
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].outcome, SessionOutcome::Completed);
+            assert_eq!(
+                sessions[0].ended_at,
+                snapshot.saved_at + chrono::Duration::seconds(15)
+            );
+        }
+    }
+
+    mod run_for {
+        use super::*;
+
+        #[test]
+        fn should_count_the_time_up_to_the_next_phase() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+
+            assert!(!pomodoro.run_for(Duration::from_secs(5)));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(5)
+            );
+
+            assert!(pomodoro.run_for(Duration::from_secs(600)));
+            assert_eq!(pomodoro.get_mode(), TimerType::Rest);
+            assert_eq!(pomodoro.get_current_timer(), Timer::new(REST_TIME));
+            assert_eq!(pomodoro.history_get().sessions.len(), 1);
+        }
+    }
+
+    mod suspend {
+        use super::*;
+
+        fn slept(secs: i64) -> (DateTime<Local>, DateTime<Local>) {
+            let to = Local::now();
+            (to - chrono::Duration::seconds(secs), to)
+        }
+
+        #[test]
+        fn should_leave_the_timer_when_pausing() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.forward();
+            let (from, to) = slept(3600);
+
+            assert!(!pomodoro.suspend(from, to, SuspendHandling::Pause));
+
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(1)
+            );
+            let suspends = &pomodoro.history_get().suspends;
+            assert_eq!(suspends.len(), 1);
+            assert_eq!(suspends[0].mode, TimerType::Focus);
+            assert_eq!(suspends[0].duration(), Duration::from_secs(3600));
+        }
+
+        #[test]
+        fn should_run_the_timer_when_counting() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let (from, to) = slept(5);
+
+            assert!(!pomodoro.suspend(from, to, SuspendHandling::Count));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(5)
+            );
+
+            let (from, to) = slept(20);
+            assert!(pomodoro.suspend(from, to, SuspendHandling::Count));
+            assert_eq!(pomodoro.get_mode(), TimerType::Rest);
+            assert_eq!(pomodoro.history_get().sessions.len(), 1);
+        }
+
+        #[test]
+        fn should_count_whole_seconds_only() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let to = Local::now();
+            let from = to - chrono::Duration::milliseconds(5700);
+
+            assert!(!pomodoro.suspend(from, to, SuspendHandling::Count));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(5)
+            );
+            assert_eq!(
+                pomodoro.history_get().suspends[0].duration(),
+                Duration::from_millis(5700)
+            );
+        }
+
+        #[test]
+        fn should_restart_the_phase_when_voiding() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.forward();
+            let (from, to) = slept(3600);
+
+            assert!(!pomodoro.suspend(from, to, SuspendHandling::Void));
+
+            assert_eq!(pomodoro.get_current_timer(), Timer::new(FOCUS_TIME));
+            let sessions = &pomodoro.history_get().sessions;
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].outcome, SessionOutcome::Voided);
+        }
+    }
+
+    mod idle_pause {
+        use super::*;
+
+        #[test]
+        fn should_give_the_idle_time_back_and_log_it() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for _ in 0..10 {
+                pomodoro.forward();
+            }
+            let to = Local::now();
+            let from = to - chrono::Duration::seconds(8);
+
+            assert!(pomodoro.idle_pause(from, to));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(2)
+            );
+
+            pomodoro.void_current();
+            let session = &pomodoro.history_get().sessions[0];
+            assert_eq!(session.idle, vec![Idle { from, to }]);
+        }
+
+        #[test]
+        fn should_give_back_whole_seconds_only() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            for _ in 0..10 {
+                pomodoro.forward();
+            }
+            let to = Local::now();
+            let from = to - chrono::Duration::milliseconds(3400);
+
+            assert!(pomodoro.idle_pause(from, to));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(7)
+            );
+        }
+
+        #[test]
+        fn should_only_apply_to_focus_and_be_undoable() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.forward();
+            let to = Local::now();
+            let from = to - chrono::Duration::hours(1);
+
+            assert!(pomodoro.idle_pause(from, to));
+            assert_eq!(pomodoro.get_current_timer(), Timer::new(FOCUS_TIME));
+            assert_eq!(pomodoro.undo(), Some("idle pause".to_string()));
+            assert_eq!(
+                pomodoro.get_current_timer().current_time,
+                FOCUS_TIME - Duration::from_secs(1)
+            );
+
+            pomodoro.next_mode();
+            assert!(!pomodoro.idle_pause(from, to));
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn should_read_each_file_on_its_own() {
+            let dir = std::env::temp_dir().join(format!("tomatoes-load-{}", std::process::id()));
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.data_dir_set(&dir);
+            pomodoro.task_add(Task::new("First", ""));
+            pomodoro.save().unwrap();
+            std::fs::write(dir.join("history"), "{\"sessions\":[").unwrap();
+
+            let mut loaded = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            loaded.data_dir_set(&dir);
+            let result = loaded.load();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            assert!(result.is_err());
+            assert_eq!(loaded.task_get_by_complete(false).len(), 1);
+        }
+    }
+
+    mod hooks_set {
+        use super::*;
+
+        #[test]
+        fn should_run_hooks_on_timer_and_task_events() {
+            let path = std::env::temp_dir().join(format!("tomatoes-hooks-{}", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            let mut hooks = Hooks::default();
+            for event in [
+                HookEvent::FocusStart,
+                HookEvent::FocusEnd,
+                HookEvent::TaskDone,
+            ] {
+                hooks.set(
+                    event,
+                    format!("echo \"$TOMATOES_EVENT\" >> {}", path.display()),
+                );
+            }
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            pomodoro.alarm_disable();
+            pomodoro.hooks_set(hooks);
+            pomodoro.task_add(Task::new("First", ""));
+
+            for _ in 0..FOCUS_TIME.as_secs() {
+                pomodoro.forward();
+            }
+            pomodoro.forward();
+            pomodoro.task_complete(0);
+            pomodoro.hooks_wait();
+
+            let output = std::fs::read_to_string(&path).unwrap_or_default();
+            let _ = std::fs::remove_file(&path);
+
+            let mut lines: Vec<_> = output.lines().collect();
+            lines.sort();
+            assert_eq!(lines, vec!["focus_end", "focus_start", "task_done"]);
+        }
+    }
+
+    mod timer_sync {
+        use super::*;
+
+        #[test]
+        fn should_mirror_the_timer_and_preset() {
+            let mut pomodoro = Pomodoro::new(FOCUS_TIME, REST_TIME);
+            let deep = Preset::new("deep", FOCUS_TIME * 2, REST_TIME * 2);
+            pomodoro.presets_set(vec![pomodoro.preset_get().clone(), deep.clone()]);
+            let timer = Timer {
+                current_time: Duration::from_secs(4),
+                initial_time: REST_TIME * 2,
+                elapsed_mark: None,
+            };
+
+            pomodoro.timer_sync(TimerType::Rest, timer, "deep");
+
+            assert_eq!(pomodoro.get_mode(), TimerType::Rest);
+            assert_eq!(pomodoro.get_current_timer(), timer);
+            assert_eq!(pomodoro.preset_get(), &deep);
+            assert!(pomodoro.history_get().sessions.is_empty());
+            assert_eq!(pomodoro.revision(), 0);
+        }
+    }
 }
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::timer::Timer;
+
+pub const DEFAULT_PRESET: &str = "default";
+
+/// A named rhythm of focus and rest, like 50/10 for deep work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub name: String,
+    pub focus: Duration,
+    pub rest: Duration,
+    pub long_rest: Duration,
+    /// Completed focus blocks before a long rest, 0 for no long rests.
+    pub long_rest_every: u32,
+}
+
+impl Preset {
+    pub fn new(name: impl Into<String>, focus: Duration, rest: Duration) -> Self {
+        Self {
+            name: name.into(),
+            focus,
+            rest,
+            long_rest: rest,
+            long_rest_every: 0,
+        }
+    }
+}
+
+/// What switching presets does to the phase that is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseChange {
+    /// Keeps the time left, up to the length of the new phase.
+    Keep,
+    /// Starts the phase over with the new length.
+    Restart,
+    /// Keeps the same fraction of the phase left.
+    Scale,
+}
+
+impl PhaseChange {
+    /// The running `timer` moved to a phase of `initial_time`.
+    pub fn apply(&self, timer: Timer, initial_time: Duration) -> Timer {
+        let current_time = match self {
+            PhaseChange::Keep => timer.current_time.min(initial_time),
+            PhaseChange::Restart => initial_time,
+            PhaseChange::Scale if timer.initial_time.is_zero() => initial_time,
+            PhaseChange::Scale => {
+                let left = timer.current_time.as_secs_f64() / timer.initial_time.as_secs_f64();
+                Duration::from_secs((initial_time.as_secs_f64() * left).round() as u64)
+            }
+        };
+
+        let current_time = current_time.max(Duration::from_secs(1));
+        let elapsed_mark = match self {
+            PhaseChange::Restart => None,
+            _ => Some(timer.elapsed() + current_time),
+        };
+        Timer {
+            current_time,
+            initial_time,
+            elapsed_mark,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod apply {
+        use super::*;
+
+        const MINUTE: Duration = Duration::from_secs(60);
+
+        fn running(initial: u32, left: u32) -> Timer {
+            Timer {
+                current_time: MINUTE * left,
+                initial_time: MINUTE * initial,
+                elapsed_mark: None,
+            }
+        }
+
+        #[test]
+        fn should_keep_the_time_left() {
+            let timer = PhaseChange::Keep.apply(running(25, 10), MINUTE * 50);
+            assert_eq!(timer.current_time, MINUTE * 10);
+            assert_eq!(timer.initial_time, MINUTE * 50);
+            assert_eq!(timer.elapsed(), MINUTE * 15);
+
+            let timer = PhaseChange::Keep.apply(running(50, 40), MINUTE * 25);
+            assert_eq!(timer.current_time, MINUTE * 25);
+        }
+
+        #[test]
+        fn should_restart_the_phase() {
+            let timer = PhaseChange::Restart.apply(running(25, 10), MINUTE * 50);
+
+            assert_eq!(timer, Timer::new(MINUTE * 50));
+        }
+
+        #[test]
+        fn should_scale_the_time_left() {
+            let timer = PhaseChange::Scale.apply(running(25, 10), MINUTE * 50);
+            assert_eq!(timer.current_time, MINUTE * 20);
+            assert_eq!(timer.elapsed(), MINUTE * 15);
+
+            let timer = PhaseChange::Scale.apply(running(50, 10), MINUTE * 15);
+            assert_eq!(timer.current_time, MINUTE * 3);
+        }
+    }
+}
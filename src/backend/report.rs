@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use super::goal::format_hours;
+use super::history::{History, InterruptionTotals, TaskStats};
+use super::task::Task;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstimateRow {
+    pub name: String,
+    pub estimated: u32,
+    pub actual: u32,
+}
+
+impl EstimateRow {
+    /// Positive when the task took more pomodoros than estimated.
+    pub fn error(&self) -> i64 {
+        self.actual as i64 - self.estimated as i64
+    }
+}
+
+/// Focus spent on the tasks of a project or tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupRow {
+    pub name: String,
+    pub pomodoros: u32,
+    pub focus: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub estimates: Vec<EstimateRow>,
+    pub interruptions: InterruptionTotals,
+    pub projects: Vec<GroupRow>,
+    pub tags: Vec<GroupRow>,
+}
+
+impl Report {
+    pub fn new(tasks: &[Task], history: &History) -> Self {
+        let task_stats = history.focus_by_task();
+
+        let estimates = tasks
+            .iter()
+            .filter(|task| task.completed)
+            .filter_map(|task| {
+                let estimated = task.estimate?;
+                let actual = task_stats
+                    .get(&task.id)
+                    .map(|stats| stats.pomodoros)
+                    .unwrap_or(0);
+
+                Some(EstimateRow {
+                    name: task.name.clone(),
+                    estimated,
+                    actual,
+                })
+            })
+            .collect();
+
+        let mut projects: BTreeMap<String, TaskStats> = BTreeMap::new();
+        let mut tags: BTreeMap<String, TaskStats> = BTreeMap::new();
+        for task in tasks.iter() {
+            let stats = match task_stats.get(&task.id) {
+                Some(stats) => stats,
+                None => continue,
+            };
+
+            if let Some(project) = &task.project {
+                Self::group_add(&mut projects, format!("+{project}"), stats);
+            }
+            for tag in task.tags.iter() {
+                Self::group_add(&mut tags, format!("@{tag}"), stats);
+            }
+        }
+
+        Self {
+            estimates,
+            interruptions: history.interruption_totals(),
+            projects: Self::group_rows(projects),
+            tags: Self::group_rows(tags),
+        }
+    }
+
+    fn group_add(groups: &mut BTreeMap<String, TaskStats>, name: String, stats: &TaskStats) {
+        let group = groups.entry(name).or_default();
+        group.pomodoros += stats.pomodoros;
+        group.focus += stats.focus;
+    }
+
+    /// Most focused groups first.
+    fn group_rows(groups: BTreeMap<String, TaskStats>) -> Vec<GroupRow> {
+        let mut rows: Vec<GroupRow> = groups
+            .into_iter()
+            .map(|(name, stats)| GroupRow {
+                name,
+                pomodoros: stats.pomodoros,
+                focus: stats.focus,
+            })
+            .collect();
+        rows.sort_by_key(|row| std::cmp::Reverse(row.focus));
+        rows
+    }
+
+    /// Number of completed tasks that didn't need more than the estimate.
+    pub fn estimate_hits(&self) -> usize {
+        self.estimates.iter().filter(|row| row.error() <= 0).count()
+    }
+
+    /// Mean absolute difference between estimated and actual pomodoros.
+    pub fn estimate_mean_error(&self) -> Option<f64> {
+        if self.estimates.is_empty() {
+            return None;
+        }
+
+        let total_error: i64 = self.estimates.iter().map(|row| row.error().abs()).sum();
+        Some(total_error as f64 / self.estimates.len() as f64)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Interruptions: {} internal | {} external | {} voided pomodoros",
+            self.interruptions.internal, self.interruptions.external, self.interruptions.voided
+        )?;
+        writeln!(f)?;
+
+        for (title, rows) in [("Projects:", &self.projects), ("Tags:", &self.tags)] {
+            writeln!(f, "{title}")?;
+            if rows.is_empty() {
+                writeln!(f, "  Nothing tracked yet.")?;
+            }
+            for row in rows.iter() {
+                writeln!(
+                    f,
+                    "  {}: {} pomodoros | {}",
+                    row.name,
+                    row.pomodoros,
+                    format_hours(row.focus)
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "Estimates (completed tasks):")?;
+        match self.estimate_mean_error() {
+            Some(mean_error) => {
+                for row in self.estimates.iter() {
+                    writeln!(
+                        f,
+                        "  {}: {} estimated, {} actual ({:+})",
+                        row.name,
+                        row.estimated,
+                        row.actual,
+                        row.error()
+                    )?;
+                }
+                writeln!(
+                    f,
+                    "  Within estimate: {}/{} | Average error: {:.1} pomodoros",
+                    self.estimate_hits(),
+                    self.estimates.len(),
+                    mean_error
+                )?;
+            }
+            None => writeln!(f, "  No completed task with an estimate.")?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::history::{Session, SessionOutcome};
+    use crate::backend::timer::TimerType;
+    use std::time::Duration;
+
+    fn completed_task(estimate: Option<u32>, pomodoros: u32, history: &mut History) -> Task {
+        let mut task = Task::new("Name", "Description");
+        task.estimate = estimate;
+        task.completed = true;
+
+        for _ in 0..pomodoros {
+            let mut session = Session::new(
+                TimerType::Focus,
+                Duration::from_secs(25 * 60),
+                SessionOutcome::Completed,
+            );
+            session.task = Some(task.id);
+            history.push(session);
+        }
+        task
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn should_compare_estimates_of_completed_tasks() {
+            let mut history = History::default();
+            let mut not_completed = completed_task(Some(2), 1, &mut history);
+            not_completed.completed = false;
+            let tasks = vec![
+                completed_task(Some(3), 4, &mut history),
+                completed_task(Some(2), 2, &mut history),
+                completed_task(None, 1, &mut history),
+                not_completed,
+            ];
+
+            let report = Report::new(&tasks, &history);
+
+            assert_eq!(report.estimates.len(), 2);
+            assert_eq!(report.estimates[0].estimated, 3);
+            assert_eq!(report.estimates[0].actual, 4);
+            assert_eq!(report.estimates[0].error(), 1);
+            assert_eq!(report.estimate_hits(), 1);
+            assert_eq!(report.estimate_mean_error(), Some(0.5));
+        }
+
+        #[test]
+        fn should_group_focus_by_project_and_tag() {
+            let mut history = History::default();
+            let mut tasks = vec![
+                completed_task(None, 2, &mut history),
+                completed_task(None, 1, &mut history),
+                completed_task(None, 3, &mut history),
+            ];
+            tasks[0].project = Some("work".to_string());
+            tasks[0].tags = vec!["code".to_string()];
+            tasks[1].project = Some("work".to_string());
+            tasks[1].tags = vec!["code".to_string(), "review".to_string()];
+            tasks[2].project = Some("home".to_string());
+
+            let report = Report::new(&tasks, &history);
+
+            let projects: Vec<(&str, u32)> = report
+                .projects
+                .iter()
+                .map(|row| (row.name.as_str(), row.pomodoros))
+                .collect();
+            assert_eq!(projects, vec![("+home", 3), ("+work", 3)]);
+            let tags: Vec<(&str, u32)> = report
+                .tags
+                .iter()
+                .map(|row| (row.name.as_str(), row.pomodoros))
+                .collect();
+            assert_eq!(tags, vec![("@code", 3), ("@review", 1)]);
+        }
+
+        #[test]
+        fn should_have_no_mean_error_without_estimates() {
+            let report = Report::new(&[], &History::default());
+
+            assert_eq!(report.estimate_mean_error(), None);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::history::{Idle, Interruption};
+use super::timer::{Timer, TimerType};
+
+/// How often a running timer is saved, besides when it starts, stops or
+/// switches phases.
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The running timer as saved in the data dir, to pick it up again after a
+/// restart or a crash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Snapshot {
+    /// `Focus` or `Rest`, never `Transitioning`.
+    pub mode: TimerType,
+    pub timer: Timer,
+    pub paused: bool,
+    pub preset: String,
+    /// Completed focus blocks since the last long rest.
+    pub focus_count: u32,
+    pub active_task: Option<u64>,
+    #[serde(default)]
+    pub interruptions: Vec<Interruption>,
+    #[serde(default)]
+    pub idle: Vec<Idle>,
+    pub saved_at: DateTime<Local>,
+}
+
+impl Snapshot {
+    /// How long the timer kept running since it was saved, as of `now`.
+    pub fn elapsed(&self, now: DateTime<Local>) -> Duration {
+        if self.paused {
+            return Duration::ZERO;
+        }
+        (now - self.saved_at).to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Whether resuming would give the same timer as starting over.
+    pub fn is_fresh(&self) -> bool {
+        self.paused
+            && self.mode == TimerType::Focus
+            && self.timer.current_time == self.timer.initial_time
+            && self.focus_count == 0
+            && self.interruptions.is_empty()
+    }
+}
+
+impl std::fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = if self.paused { "paused" } else { "running" };
+        write!(
+            f,
+            "{} with {} left, {state} since {}",
+            self.mode,
+            self.timer,
+            self.saved_at.format("%H:%M")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(paused: bool) -> Snapshot {
+        Snapshot {
+            mode: TimerType::Focus,
+            timer: Timer::new(Duration::from_secs(60)),
+            paused,
+            preset: "default".to_string(),
+            focus_count: 0,
+            active_task: None,
+            interruptions: vec![],
+            idle: vec![],
+            saved_at: Local::now(),
+        }
+    }
+
+    mod elapsed {
+        use super::*;
+
+        #[test]
+        fn should_count_wall_clock_time_while_running() {
+            let snapshot = snapshot(false);
+            let now = snapshot.saved_at + chrono::Duration::seconds(90);
+
+            assert_eq!(snapshot.elapsed(now), Duration::from_secs(90));
+            assert_eq!(
+                snapshot.elapsed(snapshot.saved_at - chrono::Duration::seconds(5)),
+                Duration::ZERO
+            );
+        }
+
+        #[test]
+        fn should_not_count_time_while_paused() {
+            let snapshot = snapshot(true);
+            let now = snapshot.saved_at + chrono::Duration::seconds(90);
+
+            assert_eq!(snapshot.elapsed(now), Duration::ZERO);
+        }
+    }
+
+    mod is_fresh {
+        use super::*;
+
+        #[test]
+        fn should_only_be_fresh_before_anything_happened() {
+            assert!(snapshot(true).is_fresh());
+            assert!(!snapshot(false).is_fresh());
+
+            let mut started = snapshot(true);
+            started.timer.current_time -= Duration::from_secs(1);
+            assert!(!started.is_fresh());
+        }
+    }
+}
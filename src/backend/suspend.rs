@@ -0,0 +1,89 @@
+use chrono::{DateTime, Local};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Shortest sleep taken as the computer being suspended rather than the
+/// wall clock being adjusted.
+pub const SUSPEND_GAP: Duration = Duration::from_secs(30);
+
+/// Tells a computer that slept apart from a loop that was only slow, like
+/// while an editor was open: the monotonic clock stops during sleep, the
+/// wall clock doesn't.
+#[derive(Debug, Clone, Copy)]
+pub struct SleepWatch {
+    wall: SystemTime,
+    monotonic: Instant,
+}
+
+impl Default for SleepWatch {
+    fn default() -> Self {
+        Self {
+            wall: SystemTime::now(),
+            monotonic: Instant::now(),
+        }
+    }
+}
+
+impl SleepWatch {
+    /// When the computer slept since the last check, if for at least
+    /// `SUSPEND_GAP`.
+    pub fn check(&mut self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        self.check_at(SystemTime::now(), Instant::now())
+    }
+
+    fn check_at(
+        &mut self,
+        wall: SystemTime,
+        monotonic: Instant,
+    ) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let wall_elapsed = wall.duration_since(self.wall).unwrap_or(Duration::ZERO);
+        let awake = monotonic.duration_since(self.monotonic);
+        self.wall = wall;
+        self.monotonic = monotonic;
+
+        let slept = wall_elapsed.saturating_sub(awake);
+        if slept < SUSPEND_GAP {
+            return None;
+        }
+        let to = DateTime::<Local>::from(wall);
+        Some((to - chrono::Duration::from_std(slept).ok()?, to))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod check {
+        use super::*;
+
+        #[test]
+        fn should_find_time_the_monotonic_clock_missed() {
+            let mut watch = SleepWatch::default();
+            let (wall, monotonic) = (watch.wall, watch.monotonic);
+
+            let slept = watch.check_at(
+                wall + Duration::from_secs(3601),
+                monotonic + Duration::from_secs(1),
+            );
+
+            let (from, to) = slept.unwrap();
+            assert_eq!(to - from, chrono::Duration::seconds(3600));
+            assert_eq!(
+                to,
+                DateTime::<Local>::from(wall + Duration::from_secs(3601))
+            );
+        }
+
+        #[test]
+        fn should_ignore_slow_loops_and_short_gaps() {
+            let mut watch = SleepWatch::default();
+            let (wall, monotonic) = (watch.wall, watch.monotonic);
+
+            let blocked = Duration::from_secs(600);
+            assert_eq!(watch.check_at(wall + blocked, monotonic + blocked), None);
+
+            let adjusted = wall + blocked + Duration::from_secs(5);
+            assert_eq!(watch.check_at(adjusted, monotonic + blocked), None);
+        }
+    }
+}
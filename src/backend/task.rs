@@ -1,27 +1,496 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The most pomodoros a task can be estimated at.
+pub const ESTIMATE_MAX: u32 = 99;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    /// Parses the `1`, `2` or `3` after a `!`.
+    pub fn from_level(level: &str) -> Option<Self> {
+        match level {
+            "1" => Some(Priority::High),
+            "2" => Some(Priority::Medium),
+            "3" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    /// Cycles no priority -> high -> medium -> low -> no priority.
+    pub fn cycle(priority: Option<Priority>) -> Option<Priority> {
+        match priority {
+            None => Some(Priority::High),
+            Some(Priority::High) => Some(Priority::Medium),
+            Some(Priority::Medium) => Some(Priority::Low),
+            Some(Priority::Low) => None,
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            Priority::High => 1,
+            Priority::Medium => 2,
+            Priority::Low => 3,
+        };
+        write!(f, "!{level}")
+    }
+}
+
+/// Parses `today`, `tomorrow`, `yesterday`, a weekday (`fri`, `friday`, the
+/// next one from `today` on), an offset (`3d`, `+2w`) or `YYYY-MM-DD`.
+pub fn parse_date(s: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let s = s.to_lowercase();
+    match s.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+
+    if let Ok(weekday) = s.parse::<Weekday>() {
+        let days =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        return today.checked_add_days(Days::new(days as u64));
+    }
+
+    let offset = s.strip_prefix('+').unwrap_or(&s);
+    if let Some(Ok(days)) = offset.strip_suffix('d').map(str::parse::<u64>) {
+        return today.checked_add_days(Days::new(days));
+    }
+    if let Some(Ok(weeks)) = offset.strip_suffix('w').map(str::parse::<u64>) {
+        return today.checked_add_days(Days::new(weeks.checked_mul(7)?));
+    }
+
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()
+}
+
+/// Formats a date relative to `today` when close to it, `YYYY-MM-DD` otherwise.
+pub fn format_date(date: NaiveDate, today: NaiveDate) -> String {
+    if date == today {
+        "today".to_string()
+    } else if today.succ_opt() == Some(date) {
+        "tomorrow".to_string()
+    } else if today.pred_opt() == Some(date) {
+        "yesterday".to_string()
+    } else {
+        date.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// How often a task repeats. Completing a recurring task creates the next
+/// occurrence.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekdays,
+    Weekly(Weekday),
+    EveryDays(u32),
+}
+
+impl Recurrence {
+    /// Parses `daily`, `weekdays`, a weekday like `fri` or `friday` for
+    /// weekly, or `Nd` for every N days.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.to_lowercase();
+        match s.as_str() {
+            "daily" => return Some(Recurrence::Daily),
+            "weekdays" => return Some(Recurrence::Weekdays),
+            _ => {}
+        }
+
+        if let Ok(weekday) = s.parse::<Weekday>() {
+            return Some(Recurrence::Weekly(weekday));
+        }
+        match s.strip_suffix('d').map(str::parse::<u32>) {
+            Some(Ok(days)) if days > 0 => Some(Recurrence::EveryDays(days)),
+            _ => None,
+        }
+    }
+
+    /// The first day after `date` the task comes back, if it's still a
+    /// date chrono can hold.
+    pub fn next(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let mut next = date.checked_add_days(Days::new(1))?;
+        match self {
+            Recurrence::Daily => {}
+            Recurrence::Weekdays => {
+                while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                    next = next.checked_add_days(Days::new(1))?;
+                }
+            }
+            Recurrence::Weekly(weekday) => {
+                while next.weekday() != *weekday {
+                    next = next.checked_add_days(Days::new(1))?;
+                }
+            }
+            Recurrence::EveryDays(days) => next = date.checked_add_days(Days::new(*days as u64))?,
+        }
+        Some(next)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekdays => write!(f, "weekdays"),
+            Recurrence::Weekly(weekday) => write!(f, "{}", weekday.to_string().to_lowercase()),
+            Recurrence::EveryDays(days) => write!(f, "{days}d"),
+        }
+    }
+}
+
+/// A checklist item inside a task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Subtask {
+    pub name: String,
+    pub completed: bool,
+}
+
+impl Subtask {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            completed: false,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Task {
+    pub id: u64,
     pub name: String,
     pub description: String,
     pub completed: bool,
+    #[serde(default)]
+    pub estimate: Option<u32>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub scheduled: Option<NaiveDate>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// The occurrence added when this recurring task was completed, so that
+    /// completing it again after reopening it doesn't add another.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurred_as: Option<u64>,
+    /// Ids of the tasks that have to be completed before this one.
+    #[serde(default)]
+    pub blocked_by: Vec<u64>,
+    /// Free form, multiline notes.
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Local>>,
 }
 
 impl Task {
     pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
         Self {
+            id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
             name: name.into(),
             description: description.into(),
             completed: false,
+            estimate: None,
+            priority: None,
+            project: None,
+            tags: vec![],
+            subtasks: vec![],
+            due: None,
+            scheduled: None,
+            recurrence: None,
+            recurred_as: None,
+            blocked_by: vec![],
+            notes: String::new(),
+            created_at: Some(Local::now()),
+            completed_at: None,
+        }
+    }
+
+    /// Makes sure tasks created from now on don't reuse `id`.
+    pub fn id_reserve(id: u64) {
+        NEXT_TASK_ID.fetch_max(id + 1, Ordering::Relaxed);
+    }
+
+    /// Copies the task as a new, not completed, task.
+    pub fn duplicate(&self) -> Self {
+        let subtasks = self
+            .subtasks
+            .iter()
+            .map(|subtask| Subtask::new(subtask.name.clone()))
+            .collect();
+
+        Self {
+            id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
+            completed: false,
+            subtasks,
+            recurred_as: None,
+            created_at: Some(Local::now()),
+            completed_at: None,
+            ..self.clone()
         }
     }
 
+    /// Parses `Name: description`. Anywhere in it, `~N` sets the estimated
+    /// pomodoros, `!1` to `!3` the priority, `+name` the project, `@name`
+    /// adds a tag, `due:date` or `sched:date` set the dates, as read by
+    /// `parse_date`, `rec:rule` the recurrence and `dep:1,2` the ids of the
+    /// tasks blocking this one.
     pub fn from_str(s: &str) -> Self {
-        let split: Vec<&str> = s.split(':').collect();
-        let name = split[0].trim();
-        let description = split.get(1).unwrap_or(&"").trim();
+        Self::from_str_on(s, Local::now().date_naive())
+    }
+
+    /// `from_str`, reading relative dates from `today`.
+    pub fn from_str_on(s: &str, today: NaiveDate) -> Self {
+        let date_of = |word: &str, keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| word.strip_prefix(key))
+                .and_then(|date| parse_date(date, today))
+        };
 
-        Self::new(name, description)
+        let mut task = Self::new("", "");
+        let mut words: Vec<&str> = vec![];
+        for word in s.split_whitespace() {
+            if let Some(due) = date_of(word, &["due:"]) {
+                task.due = Some(due);
+            } else if let Some(scheduled) = date_of(word, &["sched:", "scheduled:"]) {
+                task.scheduled = Some(scheduled);
+            } else if let Some(recurrence) = word.strip_prefix("rec:").and_then(Recurrence::parse) {
+                task.recurrence = Some(recurrence);
+            } else if let Some(Ok(ids)) = word.strip_prefix("dep:").map(|ids| {
+                ids.split(',')
+                    .map(str::parse::<u64>)
+                    .collect::<Result<Vec<u64>, _>>()
+            }) {
+                for id in ids {
+                    if !task.blocked_by.contains(&id) {
+                        task.blocked_by.push(id);
+                    }
+                }
+            } else if let Some(n) = word
+                .strip_prefix('~')
+                .and_then(|n| n.parse::<u32>().ok())
+                .filter(|n| *n <= ESTIMATE_MAX)
+            {
+                task.estimate = Some(n);
+            } else if let Some(p) = word.strip_prefix('!').and_then(Priority::from_level) {
+                task.priority = Some(p);
+            } else if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+                task.project = Some(project.to_string());
+            } else if let Some(tag) = word.strip_prefix('@').filter(|t| !t.is_empty()) {
+                if !task.tags.iter().any(|t| t == tag) {
+                    task.tags.push(tag.to_string());
+                }
+            } else {
+                words.push(word);
+            }
+        }
+        let s = words.join(" ");
+
+        let split: Vec<&str> = s.splitn(2, ':').collect();
+        task.name = split[0].trim().to_string();
+        task.description = split.get(1).unwrap_or(&"").trim().to_string();
+        task
+    }
+
+    /// The text `from_str` would parse back into this task.
+    pub fn to_input_string(&self) -> String {
+        let mut s = self.name.clone();
+        if !self.description.is_empty() {
+            s += &format!(": {}", self.description);
+        }
+        if let Some(estimate) = self.estimate {
+            s += &format!(" ~{estimate}");
+        }
+        if let Some(priority) = self.priority {
+            s += &format!(" {priority}");
+        }
+        if let Some(project) = &self.project {
+            s += &format!(" +{project}");
+        }
+        for tag in self.tags.iter() {
+            s += &format!(" @{tag}");
+        }
+        if let Some(due) = self.due {
+            s += &format!(" due:{}", due.format("%Y-%m-%d"));
+        }
+        if let Some(scheduled) = self.scheduled {
+            s += &format!(" sched:{}", scheduled.format("%Y-%m-%d"));
+        }
+        if let Some(recurrence) = self.recurrence {
+            s += &format!(" rec:{recurrence}");
+        }
+        if !self.blocked_by.is_empty() {
+            s += &format!(" dep:{}", Self::ids_string(&self.blocked_by));
+        }
+        s
+    }
+
+    /// Ids separated by commas, like `1,2`.
+    pub fn ids_string(ids: &[u64]) -> String {
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Updates the fields `from_str` knows about, keeping the rest.
+    pub fn edit_from_str(&mut self, s: &str) {
+        let parsed = Self::from_str(s);
+        self.name = parsed.name;
+        self.description = parsed.description;
+        self.estimate = parsed.estimate;
+        self.priority = parsed.priority;
+        self.project = parsed.project;
+        self.tags = parsed.tags;
+        self.due = parsed.due;
+        self.scheduled = parsed.scheduled;
+        self.recurrence = parsed.recurrence;
+        self.blocked_by = parsed.blocked_by;
+        self.blocked_by.retain(|id| *id != self.id);
+    }
+
+    /// The copy of a recurring task to do next, with its dates moved to the
+    /// next occurrence that isn't before `today`. A task without dates gets
+    /// scheduled. `None` once the task already recurred, or when the dates
+    /// would go past what chrono can hold.
+    pub fn next_occurrence(&self, today: NaiveDate) -> Option<Self> {
+        let recurrence = self.recurrence.filter(|_| self.recurred_as.is_none())?;
+        let anchor = self.scheduled.or(self.due).unwrap_or(today);
+
+        let mut next = recurrence.next(anchor)?;
+        while next < today {
+            next = recurrence.next(next)?;
+        }
+        let shift = next - anchor;
+        let shifted = |date: Option<NaiveDate>| match date {
+            Some(date) => date.checked_add_signed(shift).map(Some),
+            None => Some(None),
+        };
+
+        let mut task = self.duplicate();
+        task.due = shifted(self.due)?;
+        task.scheduled = match (self.scheduled, self.due) {
+            (None, None) => Some(next),
+            (scheduled, _) => shifted(scheduled)?,
+        };
+        Some(task)
+    }
+
+    /// Marks the task as completed or not, keeping track of when it was
+    /// completed.
+    pub fn complete_set(&mut self, completed: bool) {
+        if completed && !self.completed {
+            self.completed_at = Some(Local::now());
+        } else if !completed {
+            self.completed_at = None;
+        }
+        self.completed = completed;
+    }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due.is_some_and(|due| due < today)
+    }
+
+    /// Scheduled for today or earlier, or due by today.
+    pub fn is_for_today(&self, today: NaiveDate) -> bool {
+        self.scheduled.is_some_and(|scheduled| scheduled <= today)
+            || self.due.is_some_and(|due| due <= today)
+    }
+
+    /// Completed pomodoros against the estimate, like `●●○` or `●●●+1`.
+    pub fn estimate_progress(&self, done: u32) -> Option<String> {
+        let estimate = self.estimate?;
+        let filled = done.min(estimate) as usize;
+
+        let mut progress = "●".repeat(filled) + &"○".repeat(estimate as usize - filled);
+        if done > estimate {
+            progress += &format!("+{}", done - estimate);
+        }
+        Some(progress)
+    }
+
+    /// Done subtasks over all of them, like `2/3`.
+    pub fn subtasks_progress(&self) -> Option<String> {
+        if self.subtasks.is_empty() {
+            return None;
+        }
+
+        let done = self.subtasks.iter().filter(|s| s.completed).count();
+        Some(format!("{done}/{}", self.subtasks.len()))
+    }
+
+    pub fn subtasks_completed(&self) -> bool {
+        !self.subtasks.is_empty() && self.subtasks.iter().all(|s| s.completed)
+    }
+}
+
+/// Narrows the task lists down to one project (`+name`), tag (`@name`) or
+/// to what is planned for `today`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskFilter {
+    Project(String),
+    Tag(String),
+    Today,
+}
+
+impl TaskFilter {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(project) = s.strip_prefix('+').filter(|p| !p.is_empty()) {
+            return Some(TaskFilter::Project(project.to_string()));
+        }
+        if let Some(tag) = s.strip_prefix('@').filter(|t| !t.is_empty()) {
+            return Some(TaskFilter::Tag(tag.to_string()));
+        }
+        if s.eq_ignore_ascii_case("today") {
+            return Some(TaskFilter::Today);
+        }
+        None
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_on(task, Local::now().date_naive())
+    }
+
+    pub fn matches_on(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            TaskFilter::Project(project) => task.project.as_ref() == Some(project),
+            TaskFilter::Tag(tag) => task.tags.contains(tag),
+            TaskFilter::Today => task.is_for_today(today),
+        }
+    }
+}
+
+impl fmt::Display for TaskFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskFilter::Project(project) => write!(f, "+{project}"),
+            TaskFilter::Tag(tag) => write!(f, "@{tag}"),
+            TaskFilter::Today => write!(f, "today"),
+        }
     }
 }
 
@@ -29,6 +498,10 @@ impl Task {
 mod test {
     use super::*;
 
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
     mod new {
         use super::*;
 
@@ -48,6 +521,46 @@ mod test {
 
             assert_eq!(task.completed, false);
         }
+
+        #[test]
+        fn should_give_each_task_a_different_id() {
+            let task1 = Task::new(TASK_NAME, TASK_DESCRIPTION);
+            let task2 = Task::new(TASK_NAME, TASK_DESCRIPTION);
+
+            assert_ne!(task1.id, task2.id);
+        }
+    }
+
+    mod id_reserve {
+        use super::*;
+
+        #[test]
+        fn should_not_reuse_reserved_ids() {
+            let task = Task::new("Name", "Description");
+            Task::id_reserve(task.id + 100);
+
+            let new_task = Task::new("Name", "Description");
+            assert!(new_task.id > task.id + 100);
+        }
+    }
+
+    mod duplicate {
+        use super::*;
+
+        #[test]
+        fn should_copy_as_a_new_not_completed_task() {
+            let mut task = Task::new("Name", "Description");
+            task.completed = true;
+            task.subtasks = vec![Subtask::new("Item")];
+            task.subtasks[0].completed = true;
+
+            let copy = task.duplicate();
+            assert_ne!(copy.id, task.id);
+            assert_eq!(copy.name, task.name);
+            assert_eq!(copy.description, task.description);
+            assert!(!copy.completed);
+            assert_eq!(copy.subtasks, vec![Subtask::new("Item")]);
+        }
     }
 
     mod from_str {
@@ -72,5 +585,392 @@ mod test {
             assert_eq!(task.name, TASK_NAME);
             assert_eq!(task.description, "");
         }
+
+        #[test]
+        fn should_parse_the_estimate() {
+            let task_str = TASK_NAME.to_string() + ": " + TASK_DESCRIPTION + " ~3";
+            let task = Task::from_str(task_str.as_str());
+
+            assert_eq!(task.name, TASK_NAME);
+            assert_eq!(task.description, TASK_DESCRIPTION);
+            assert_eq!(task.estimate, Some(3));
+        }
+
+        #[test]
+        fn should_parse_the_priority() {
+            let task = Task::from_str("!1 Name: Description");
+
+            assert_eq!(task.name, "Name");
+            assert_eq!(task.priority, Some(Priority::High));
+
+            let task = Task::from_str("Name: Wow!4 !5");
+            assert_eq!(task.description, "Wow!4 !5");
+            assert_eq!(task.priority, None);
+        }
+
+        #[test]
+        fn should_parse_project_and_tags() {
+            let task = Task::from_str("+work Review PR: read diff @review @code @review");
+
+            assert_eq!(task.name, "Review PR");
+            assert_eq!(task.description, "read diff");
+            assert_eq!(task.project, Some("work".to_string()));
+            assert_eq!(task.tags, vec!["review".to_string(), "code".to_string()]);
+        }
+
+        #[test]
+        fn should_keep_lone_markers_as_text() {
+            let task = Task::from_str("Name: 1 + 1 @");
+
+            assert_eq!(task.description, "1 + 1 @");
+            assert_eq!(task.project, None);
+            assert!(task.tags.is_empty());
+        }
+
+        #[test]
+        fn should_parse_due_and_scheduled_dates() {
+            let task = Task::from_str_on(
+                "Name: Description due:2026-10-20 scheduled:tomorrow",
+                date(2026, 10, 18),
+            );
+
+            assert_eq!(task.description, "Description");
+            assert_eq!(task.due, Some(date(2026, 10, 20)));
+            assert_eq!(task.scheduled, Some(date(2026, 10, 19)));
+
+            let task = Task::from_str("Name: due:someday");
+            assert_eq!(task.description, "due:someday");
+            assert_eq!(task.due, None);
+        }
+
+        #[test]
+        fn should_parse_blocking_task_ids() {
+            let task = Task::from_str("Name: Description dep:3,5 dep:5");
+            assert_eq!(task.blocked_by, vec![3, 5]);
+            assert_eq!(task.description, "Description");
+
+            let task = Task::from_str("Name: dep:x");
+            assert!(task.blocked_by.is_empty());
+            assert_eq!(task.description, "dep:x");
+        }
+
+        #[test]
+        fn should_keep_invalid_estimates_as_text() {
+            let task_str = TASK_NAME.to_string() + ": ~three";
+            let task = Task::from_str(task_str.as_str());
+
+            assert_eq!(task.description, "~three");
+            assert_eq!(task.estimate, None);
+
+            let task = Task::from_str("Name ~100");
+            assert_eq!(task.name, "Name ~100");
+            assert_eq!(task.estimate, None);
+        }
+    }
+
+    mod to_input_string {
+        use super::*;
+
+        #[test]
+        fn should_be_parsed_back_into_the_same_fields() {
+            let task = Task::from_str("Name: Description ~2 !3 +project @a @b");
+            let parsed = Task::from_str(&task.to_input_string());
+
+            assert_eq!(
+                task.to_input_string(),
+                "Name: Description ~2 !3 +project @a @b"
+            );
+            assert_eq!(parsed.project, task.project);
+            assert_eq!(parsed.tags, task.tags);
+            assert_eq!(parsed.name, task.name);
+            assert_eq!(parsed.description, task.description);
+            assert_eq!(parsed.estimate, task.estimate);
+            assert_eq!(parsed.priority, task.priority);
+        }
+
+        #[test]
+        fn should_write_dates_in_full() {
+            let task = Task::from_str_on("Name due:tomorrow sched:today", date(2026, 10, 18));
+
+            assert_eq!(
+                task.to_input_string(),
+                "Name due:2026-10-19 sched:2026-10-18"
+            );
+        }
+    }
+
+    mod edit_from_str {
+        use super::*;
+
+        #[test]
+        fn should_keep_id_and_completion() {
+            let mut task = Task::new("Nmae", "Description");
+            task.completed = true;
+            let id = task.id;
+
+            task.edit_from_str("Name: New description");
+
+            assert_eq!(task.id, id);
+            assert!(task.completed);
+            assert_eq!(task.name, "Name");
+            assert_eq!(task.description, "New description");
+        }
+
+        #[test]
+        fn should_not_block_a_task_by_itself() {
+            let mut task = Task::new("Name", "Description");
+            let input = format!("Name dep:{},1", task.id);
+
+            task.edit_from_str(&input);
+
+            assert_eq!(task.blocked_by, vec![1]);
+            assert_eq!(task.to_input_string(), "Name dep:1");
+        }
+    }
+
+    mod task_filter {
+        use super::*;
+
+        #[test]
+        fn should_match_tasks_planned_for_today() {
+            let today = date(2026, 10, 18);
+            let filter = TaskFilter::parse("today").unwrap();
+
+            let overdue = Task::from_str_on("Name due:yesterday", today);
+            let scheduled = Task::from_str_on("Name sched:today due:fri", today);
+            let later = Task::from_str_on("Name sched:tomorrow", today);
+            let undated = Task::from_str_on("Name", today);
+
+            assert!(filter.matches_on(&overdue, today));
+            assert!(filter.matches_on(&scheduled, today));
+            assert!(!filter.matches_on(&later, today));
+            assert!(!filter.matches_on(&undated, today));
+        }
+
+        #[test]
+        fn should_match_by_project_or_tag() {
+            let task = Task::from_str("Name +work @review");
+
+            assert!(TaskFilter::parse("+work").unwrap().matches(&task));
+            assert!(!TaskFilter::parse("+home").unwrap().matches(&task));
+            assert!(TaskFilter::parse("@review").unwrap().matches(&task));
+            assert!(!TaskFilter::parse("@code").unwrap().matches(&task));
+        }
+
+        #[test]
+        fn should_only_parse_projects_and_tags() {
+            assert_eq!(TaskFilter::parse("work"), None);
+            assert_eq!(TaskFilter::parse("+"), None);
+            assert_eq!(
+                TaskFilter::parse(" @code ").map(|f| f.to_string()),
+                Some("@code".to_string())
+            );
+        }
+    }
+
+    mod complete_set {
+        use super::*;
+
+        #[test]
+        fn should_track_when_the_task_was_completed() {
+            let mut task = Task::new("Name", "Description");
+            assert!(task.created_at.is_some());
+            assert_eq!(task.completed_at, None);
+
+            task.complete_set(true);
+            let completed_at = task.completed_at;
+            assert!(task.completed);
+            assert!(completed_at.is_some());
+
+            task.complete_set(true);
+            assert_eq!(task.completed_at, completed_at);
+
+            task.complete_set(false);
+            assert!(!task.completed);
+            assert_eq!(task.completed_at, None);
+        }
+    }
+
+    mod parse_date {
+        use super::*;
+
+        #[test]
+        fn should_parse_relative_dates() {
+            // A Sunday.
+            let today = date(2026, 10, 18);
+
+            assert_eq!(parse_date("today", today), Some(today));
+            assert_eq!(parse_date("Tomorrow", today), Some(date(2026, 10, 19)));
+            assert_eq!(parse_date("yesterday", today), Some(date(2026, 10, 17)));
+            assert_eq!(parse_date("fri", today), Some(date(2026, 10, 23)));
+            assert_eq!(parse_date("sunday", today), Some(today));
+            assert_eq!(parse_date("3d", today), Some(date(2026, 10, 21)));
+            assert_eq!(parse_date("+2w", today), Some(date(2026, 11, 1)));
+            assert_eq!(parse_date("3000000000000000000w", today), None);
+            assert_eq!(parse_date("99999999999d", today), None);
+        }
+
+        #[test]
+        fn should_parse_iso_dates() {
+            let today = date(2026, 10, 18);
+
+            assert_eq!(parse_date("2026-10-20", today), Some(date(2026, 10, 20)));
+            assert_eq!(parse_date("2026-13-01", today), None);
+            assert_eq!(parse_date("soon", today), None);
+        }
+    }
+
+    mod recurrence {
+        use super::*;
+
+        #[test]
+        fn should_parse_and_print_rules() {
+            for rule in ["daily", "weekdays", "fri", "3d"] {
+                assert_eq!(Recurrence::parse(rule).unwrap().to_string(), rule);
+            }
+            assert_eq!(
+                Recurrence::parse("Monday"),
+                Some(Recurrence::Weekly(Weekday::Mon))
+            );
+            assert_eq!(Recurrence::parse("0d"), None);
+            assert_eq!(Recurrence::parse("often"), None);
+        }
+
+        #[test]
+        fn should_find_the_next_day() {
+            // A Friday.
+            let friday = date(2026, 10, 16);
+
+            assert_eq!(Recurrence::Daily.next(friday), Some(date(2026, 10, 17)));
+            assert_eq!(Recurrence::Weekdays.next(friday), Some(date(2026, 10, 19)));
+            assert_eq!(
+                Recurrence::Weekly(Weekday::Fri).next(friday),
+                Some(date(2026, 10, 23))
+            );
+            assert_eq!(
+                Recurrence::EveryDays(3).next(friday),
+                Some(date(2026, 10, 19))
+            );
+            assert_eq!(Recurrence::Daily.next(NaiveDate::MAX), None);
+            assert_eq!(Recurrence::EveryDays(u32::MAX).next(friday), None);
+        }
+    }
+
+    mod next_occurrence {
+        use super::*;
+
+        #[test]
+        fn should_move_the_dates_to_the_next_occurrence() {
+            let today = date(2026, 10, 16);
+            let mut task =
+                Task::from_str_on("Weekly report sched:today due:tomorrow rec:fri", today);
+            task.subtasks = vec![Subtask::new("Write")];
+            task.subtasks[0].completed = true;
+            task.completed = true;
+
+            let next = task.next_occurrence(today).unwrap();
+
+            assert_ne!(next.id, task.id);
+            assert!(!next.completed);
+            assert!(!next.subtasks[0].completed);
+            assert_eq!(next.recurrence, task.recurrence);
+            assert_eq!(next.scheduled, Some(date(2026, 10, 23)));
+            assert_eq!(next.due, Some(date(2026, 10, 24)));
+        }
+
+        #[test]
+        fn should_skip_missed_occurrences() {
+            let today = date(2026, 10, 18);
+            let task = Task::from_str_on("Inbox zero due:2026-10-10 rec:daily", today);
+
+            let next = task.next_occurrence(today).unwrap();
+
+            assert_eq!(next.due, Some(today));
+            assert_eq!(next.scheduled, None);
+        }
+
+        #[test]
+        fn should_schedule_tasks_without_dates() {
+            let today = date(2026, 10, 18);
+            let task = Task::from_str_on("Water plants rec:3d", today);
+
+            assert_eq!(
+                task.next_occurrence(today).unwrap().scheduled,
+                Some(date(2026, 10, 21))
+            );
+            assert!(Task::new("Name", "").next_occurrence(today).is_none());
+        }
+    }
+
+    mod is_overdue {
+        use super::*;
+
+        #[test]
+        fn should_only_be_overdue_after_the_due_date() {
+            let today = date(2026, 10, 18);
+            let mut task = Task::from_str_on("Name due:yesterday", today);
+            assert!(task.is_overdue(today));
+            assert!(!Task::from_str_on("Name due:today", today).is_overdue(today));
+
+            task.completed = true;
+            assert!(!task.is_overdue(today));
+        }
+    }
+
+    mod priority {
+        use super::*;
+
+        #[test]
+        fn should_cycle_through_levels() {
+            let mut priority = None;
+            let mut seen = vec![];
+            for _ in 0..4 {
+                priority = Priority::cycle(priority);
+                seen.push(priority);
+            }
+
+            assert_eq!(
+                seen,
+                vec![
+                    Some(Priority::High),
+                    Some(Priority::Medium),
+                    Some(Priority::Low),
+                    None
+                ]
+            );
+        }
+    }
+
+    mod estimate_progress {
+        use super::*;
+
+        #[test]
+        fn should_show_done_and_remaining_pomodoros() {
+            let mut task = Task::new("Name", "Description");
+            assert_eq!(task.estimate_progress(1), None);
+
+            task.estimate = Some(3);
+            assert_eq!(task.estimate_progress(2), Some("●●○".to_string()));
+            assert_eq!(task.estimate_progress(4), Some("●●●+1".to_string()));
+        }
+    }
+
+    mod subtasks_progress {
+        use super::*;
+
+        #[test]
+        fn should_count_done_subtasks() {
+            let mut task = Task::new("Review PR", "");
+            assert_eq!(task.subtasks_progress(), None);
+            assert!(!task.subtasks_completed());
+
+            task.subtasks = vec![Subtask::new("Read diff"), Subtask::new("Run tests")];
+            task.subtasks[0].completed = true;
+            assert_eq!(task.subtasks_progress(), Some("1/2".to_string()));
+            assert!(!task.subtasks_completed());
+
+            task.subtasks[1].completed = true;
+            assert!(task.subtasks_completed());
+        }
     }
 }
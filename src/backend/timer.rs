@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::ops::Deref;
 use std::time::Duration;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Timer {
     pub current_time: Duration,
     pub initial_time: Duration,
+    /// Set when the phase changed length while it ran: the time it had run
+    /// plus the time it had left then, so what it ran stays counted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_mark: Option<Duration>,
 }
 
 impl Timer {
@@ -13,16 +18,26 @@ impl Timer {
         Self {
             current_time: initial_time,
             initial_time,
+            elapsed_mark: None,
         }
     }
 
-    pub fn to_string(&self) -> String {
+    /// How long the phase has run.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_mark
+            .unwrap_or(self.initial_time)
+            .saturating_sub(self.current_time)
+    }
+}
+
+impl fmt::Display for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let duration = self.current_time;
 
         let secs = duration.as_secs() % 60;
         let mins = (duration.as_secs_f32() - (secs as f32)) / 60f32;
 
-        format!("{:02}:{:02}", mins, secs)
+        write!(f, "{:02}:{:02}", mins, secs)
     }
 }
 
@@ -33,17 +48,17 @@ pub enum TimerType {
     Transitioning(Box<TimerType>),
 }
 
-impl TimerType {
-    pub fn to_string(&self) -> String {
+impl fmt::Display for TimerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TimerType::Focus => String::from("Focus"),
-            TimerType::Rest => String::from("Rest"),
+            TimerType::Focus => write!(f, "Focus"),
+            TimerType::Rest => write!(f, "Rest"),
             TimerType::Transitioning(s) => {
                 let next_mode = match s.deref() {
                     TimerType::Transitioning(_) => unreachable!(),
                     _ => s.to_string(),
                 };
-                format!("Transitioning({next_mode})",)
+                write!(f, "Transitioning({next_mode})")
             }
         }
     }
@@ -0,0 +1,224 @@
+use super::history::{Idle, Interruption, Session};
+use super::preset::Preset;
+use super::task::Task;
+use super::timer::{Timer, TimerType};
+
+const UNDO_LIMIT: usize = 100;
+
+/// The timer part of the pomodoro state, before or after a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimerState {
+    pub focus: Timer,
+    pub rest: Timer,
+    pub preset: Preset,
+    pub focus_count: u32,
+    pub timer: TimerType,
+    pub interruptions: Vec<Interruption>,
+    pub idle: Vec<Idle>,
+}
+
+/// One reversible step of a command. Task indexes point into the stored
+/// tasks, not into a filtered or sorted list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    TaskInsert {
+        index: usize,
+        task: Task,
+    },
+    TaskRemove {
+        index: usize,
+        task: Task,
+    },
+    TaskReplace {
+        index: usize,
+        before: Box<Task>,
+        after: Box<Task>,
+    },
+    TaskSwap {
+        a: usize,
+        b: usize,
+    },
+    ArchiveInsert {
+        index: usize,
+        task: Task,
+    },
+    ArchiveRemove {
+        index: usize,
+        task: Task,
+    },
+    ActiveTask {
+        before: Option<u64>,
+        after: Option<u64>,
+    },
+    Timer {
+        before: Box<TimerState>,
+        after: Box<TimerState>,
+    },
+    SessionPush(Session),
+    SessionRemove(Session),
+}
+
+impl Change {
+    /// The change that takes the state back to how it was before this one.
+    pub fn inverse(&self) -> Change {
+        match self.clone() {
+            Change::TaskInsert { index, task } => Change::TaskRemove { index, task },
+            Change::TaskRemove { index, task } => Change::TaskInsert { index, task },
+            Change::TaskReplace {
+                index,
+                before,
+                after,
+            } => Change::TaskReplace {
+                index,
+                before: after,
+                after: before,
+            },
+            Change::TaskSwap { a, b } => Change::TaskSwap { a, b },
+            Change::ArchiveInsert { index, task } => Change::ArchiveRemove { index, task },
+            Change::ArchiveRemove { index, task } => Change::ArchiveInsert { index, task },
+            Change::ActiveTask { before, after } => Change::ActiveTask {
+                before: after,
+                after: before,
+            },
+            Change::Timer { before, after } => Change::Timer {
+                before: after,
+                after: before,
+            },
+            Change::SessionPush(session) => Change::SessionRemove(session),
+            Change::SessionRemove(session) => Change::SessionPush(session),
+        }
+    }
+}
+
+/// A user action, as the changes it made and a short description of it for
+/// status messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub description: String,
+    pub changes: Vec<Change>,
+}
+
+impl Command {
+    pub fn inverse(&self) -> Command {
+        Command {
+            description: self.description.clone(),
+            changes: self.changes.iter().rev().map(Change::inverse).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UndoStack {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+}
+
+impl UndoStack {
+    /// Remembers a new command, dropping the oldest past `UNDO_LIMIT` and
+    /// everything that could be redone.
+    pub fn push(&mut self, command: Command) {
+        self.undo.push(command);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// The last command done, now ready to be redone.
+    pub fn undo(&mut self) -> Option<Command> {
+        let command = self.undo.pop()?;
+        self.redo.push(command.clone());
+        Some(command)
+    }
+
+    /// The last command undone, now ready to be undone again.
+    pub fn redo(&mut self) -> Option<Command> {
+        let command = self.redo.pop()?;
+        self.undo.push(command.clone());
+        Some(command)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn command(description: &str) -> Command {
+        Command {
+            description: description.to_string(),
+            changes: vec![Change::TaskSwap { a: 0, b: 1 }],
+        }
+    }
+
+    mod inverse {
+        use super::*;
+
+        #[test]
+        fn should_revert_changes_in_reverse_order() {
+            let task = Task::new("Name", "Description");
+            let command = Command {
+                description: "add".to_string(),
+                changes: vec![
+                    Change::TaskInsert {
+                        index: 2,
+                        task: task.clone(),
+                    },
+                    Change::ActiveTask {
+                        before: None,
+                        after: Some(task.id),
+                    },
+                ],
+            };
+
+            assert_eq!(
+                command.inverse().changes,
+                vec![
+                    Change::ActiveTask {
+                        before: Some(task.id),
+                        after: None,
+                    },
+                    Change::TaskRemove { index: 2, task },
+                ]
+            );
+        }
+    }
+
+    mod push {
+        use super::*;
+
+        #[test]
+        fn should_forget_redo_after_a_new_command() {
+            let mut stack = UndoStack::default();
+            stack.push(command("first"));
+            stack.push(command("second"));
+
+            assert_eq!(stack.undo().unwrap().description, "second");
+            stack.push(command("third"));
+
+            assert_eq!(stack.redo(), None);
+            assert_eq!(stack.undo().unwrap().description, "third");
+            assert_eq!(stack.undo().unwrap().description, "first");
+            assert_eq!(stack.undo(), None);
+            assert_eq!(stack.redo().unwrap().description, "first");
+        }
+
+        #[test]
+        fn should_keep_a_limited_number_of_commands() {
+            let mut stack = UndoStack::default();
+            for i in 0..UNDO_LIMIT + 5 {
+                stack.push(command(&i.to_string()));
+            }
+
+            let mut undone = 0;
+            while stack.undo().is_some() {
+                undone += 1;
+            }
+            assert_eq!(undone, UNDO_LIMIT);
+        }
+    }
+}
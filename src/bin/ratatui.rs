@@ -1,33 +1,49 @@
-use std::time::Duration;
+use std::path::PathBuf;
 
-use tomatoes::backend::Pomodoro;
+use tomatoes::config::Config;
 use tomatoes::frontend::tui_ratatui::TuiRatatuiDisplay;
 
-fn main() {
-    let mut pomodoro = Pomodoro::new(Duration::from_secs(25 * 60), Duration::from_secs(5 * 60));
-
-    // pomodoro.task_add(Task::new("Ler cap. de AM", "Ler capítulo 2 de AM."));
-    // pomodoro.task_add(Task::new(
-    //     "Ler artigo",
-    //     "Reler do Hinton, sobre destilamento de conhecimento.",
-    // ));
-    // pomodoro.task_add(Task::new(
-    //     "Estudar Hacking",
-    //     "Fazer aulas do curso de hacker ético.",
-    // ));
-
-    // pomodoro.task_add(Task::new("Ler cap. de AM", "Ler capítulo 2 de AM."));
-
-    // pomodoro.task_complete(0);
-    // pomodoro.task_complete(3);
+const USAGE: &str = "Usage: ratatui [--config <path>]";
 
-    // let mut terminal = TerminalDisplay::new(pomodoro);
-    // terminal.pomo_loop();
-
-    let _ = pomodoro.load();
-    let res = pomodoro.save();
-
-    println!("Res: {res:?}");
-    let mut tui = TuiRatatuiDisplay::new(pomodoro).expect("Failt to create TUI");
+fn main() {
+    let mut config_path: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{arg} needs a path\n{USAGE}");
+                    std::process::exit(2);
+                }
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => {
+                eprintln!("Unknown argument: {arg}\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let config = match Config::load(config_path.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid config: {e}");
+            std::process::exit(1);
+        }
+    };
+    let mut pomodoro = config.pomodoro();
+    if let Err(e) = pomodoro.load() {
+        eprintln!(
+            "Could not read the data in {}: {e}",
+            config.data_dir().display()
+        );
+        std::process::exit(1);
+    }
+
+    let mut tui = TuiRatatuiDisplay::new(pomodoro, &config).expect("Failt to create TUI");
     tui.pomo_loop().expect("Not fail!");
 }
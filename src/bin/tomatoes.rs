@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use tomatoes::frontend::cli::{run, Cli};
+
+fn main() -> ExitCode {
+    run(Cli::parse())
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use tomatoes::config::Config;
+use tomatoes::daemon::Daemon;
+
+/// Runs the pomodoro in the background, controlled through a Unix socket.
+#[derive(Debug, Parser)]
+#[command(name = "tomatoesd")]
+struct Args {
+    /// Config file to use instead of $XDG_CONFIG_HOME/tomatoes/config.toml.
+    #[arg(short, long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Socket to listen on instead of $XDG_RUNTIME_DIR/tomatoes.sock.
+    #[arg(short, long, value_name = "PATH")]
+    socket: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("tomatoesd: invalid config: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut pomodoro = config.pomodoro();
+    if let Err(e) = pomodoro.load() {
+        eprintln!("tomatoesd: could not read the data: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    let socket = args.socket.unwrap_or_else(|| config.socket_path());
+    let listener = match Daemon::bind(&socket) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("tomatoesd: {}: {e}", socket.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("tomatoesd: listening on {}", socket.display());
+
+    match Daemon::new(pomodoro, &config).run(listener) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("tomatoesd: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Deserialize;
+
+/// Actions the command palette can run, and that keys can be bound to in the
+/// config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteAction {
+    StartPause,
+    Skip,
+    Reset,
+    TaskAdd,
+    GoTimer,
+    GoTodo,
+    GoDone,
+    AlarmToggle,
+    Report,
+    Archive,
+    ArchiveDone,
+    TodayFilter,
+    FilterClear,
+    SortByPriority,
+    Undo,
+    Redo,
+    PresetSwitch,
+    Quit,
+    /// Only for key bindings, it isn't listed in the palette.
+    PaletteOpen,
+}
+
+impl PaletteAction {
+    pub const ALL: [PaletteAction; 18] = [
+        PaletteAction::StartPause,
+        PaletteAction::Skip,
+        PaletteAction::Reset,
+        PaletteAction::TaskAdd,
+        PaletteAction::GoTimer,
+        PaletteAction::GoTodo,
+        PaletteAction::GoDone,
+        PaletteAction::AlarmToggle,
+        PaletteAction::Report,
+        PaletteAction::Archive,
+        PaletteAction::ArchiveDone,
+        PaletteAction::TodayFilter,
+        PaletteAction::FilterClear,
+        PaletteAction::SortByPriority,
+        PaletteAction::Undo,
+        PaletteAction::Redo,
+        PaletteAction::PresetSwitch,
+        PaletteAction::Quit,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaletteAction::StartPause => "Start / pause timer",
+            PaletteAction::Skip => "Skip to the next timer",
+            PaletteAction::Reset => "Reset timer",
+            PaletteAction::TaskAdd => "Add task",
+            PaletteAction::GoTimer => "Go to timer",
+            PaletteAction::GoTodo => "Go to TODO",
+            PaletteAction::GoDone => "Go to DONE",
+            PaletteAction::AlarmToggle => "Toggle alarm",
+            PaletteAction::Report => "Show report",
+            PaletteAction::Archive => "Search archive",
+            PaletteAction::ArchiveDone => "Archive done tasks",
+            PaletteAction::TodayFilter => "Show today",
+            PaletteAction::FilterClear => "Clear filter",
+            PaletteAction::SortByPriority => "Toggle sort by priority",
+            PaletteAction::Undo => "Undo",
+            PaletteAction::Redo => "Redo",
+            PaletteAction::PresetSwitch => "Switch timer preset",
+            PaletteAction::Quit => "Quit",
+            PaletteAction::PaletteOpen => "Open palette",
+        }
+    }
+
+    /// Name of the action in the `[keys]` table of the config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaletteAction::StartPause => "start_pause",
+            PaletteAction::Skip => "skip",
+            PaletteAction::Reset => "reset",
+            PaletteAction::TaskAdd => "task_add",
+            PaletteAction::GoTimer => "go_timer",
+            PaletteAction::GoTodo => "go_todo",
+            PaletteAction::GoDone => "go_done",
+            PaletteAction::AlarmToggle => "alarm_toggle",
+            PaletteAction::Report => "report",
+            PaletteAction::Archive => "archive",
+            PaletteAction::ArchiveDone => "archive_done",
+            PaletteAction::TodayFilter => "today_filter",
+            PaletteAction::FilterClear => "filter_clear",
+            PaletteAction::SortByPriority => "sort_by_priority",
+            PaletteAction::Undo => "undo",
+            PaletteAction::Redo => "redo",
+            PaletteAction::PresetSwitch => "preset_switch",
+            PaletteAction::Quit => "quit",
+            PaletteAction::PaletteOpen => "palette_open",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    mod name {
+        use super::*;
+
+        #[test]
+        fn should_match_the_config_names() {
+            for action in PaletteAction::ALL
+                .into_iter()
+                .chain([PaletteAction::PaletteOpen])
+            {
+                let toml = format!("action = \"{}\"", action.name());
+                let parsed: HashMap<String, PaletteAction> = toml::from_str(&toml).unwrap();
+                assert_eq!(parsed["action"], action);
+            }
+        }
+    }
+}
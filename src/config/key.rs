@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// A key from the config file, like `u`, `ctrl-p`, `alt-enter` or `f5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Whether `event` is this key. Shift is left out, as it is already part
+    /// of the char for letters and symbols.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match event.code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => code,
+        };
+        code == self.code && modifiers == self.modifiers
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s.trim();
+        loop {
+            let lower = rest.to_lowercase();
+            if rest.len() > 1 && lower.starts_with("ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if rest.len() > 1 && lower.starts_with("alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{s}`")),
+                },
+            },
+        };
+
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            code => write!(f, "{}", format!("{code:?}").to_lowercase()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_chars_names_and_modifiers() {
+            assert_eq!(
+                Key::parse("u"),
+                Ok(Key {
+                    code: KeyCode::Char('u'),
+                    modifiers: KeyModifiers::NONE,
+                })
+            );
+            assert_eq!(
+                Key::parse("Ctrl-P"),
+                Ok(Key {
+                    code: KeyCode::Char('p'),
+                    modifiers: KeyModifiers::CONTROL,
+                })
+            );
+            assert_eq!(Key::parse("alt-enter").unwrap().code, KeyCode::Enter);
+            assert_eq!(Key::parse("space").unwrap().code, KeyCode::Char(' '));
+            assert_eq!(Key::parse("-").unwrap().code, KeyCode::Char('-'));
+            assert_eq!(Key::parse("f5").unwrap().code, KeyCode::F(5));
+        }
+
+        #[test]
+        fn should_reject_unknown_keys() {
+            assert!(Key::parse("").is_err());
+            assert!(Key::parse("ctrl-").is_err());
+            assert!(Key::parse("f13").is_err());
+            assert_eq!(
+                Key::parse("hyper-x"),
+                Err("unknown key `hyper-x`".to_string())
+            );
+        }
+
+        #[test]
+        fn should_display_as_parsed() {
+            for key in ["u", "ctrl-p", "alt-enter", "space", "f5", "esc"] {
+                assert_eq!(Key::parse(key).unwrap().to_string(), key);
+            }
+        }
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn should_ignore_shift() {
+            let key = Key::parse("U").unwrap();
+
+            assert!(key.matches(&KeyEvent::new(KeyCode::Char('U'), KeyModifiers::SHIFT)));
+            assert!(!key.matches(&KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE)));
+        }
+
+        #[test]
+        fn should_need_the_same_modifiers() {
+            let key = Key::parse("ctrl-p").unwrap();
+
+            assert!(key.matches(&KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)));
+            assert!(!key.matches(&KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)));
+            assert!(!key.matches(&KeyEvent::new(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )));
+        }
+    }
+}
@@ -0,0 +1,813 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::backend::{
+    DailyGoal, HookEvent, Hooks, Pomodoro, Preset, SuspendHandling, ALARM_SOUND, DATA_DIR,
+    DEFAULT_PRESET, HOOK_TIMEOUT,
+};
+
+mod action;
+mod key;
+pub use action::*;
+pub use key::*;
+
+/// Settings read from `config.toml`. Every field is optional in the file and
+/// falls back to its default.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub data_dir: PathBuf,
+    pub timer: TimerConfig,
+    pub alarm: AlarmConfig,
+    pub tasks: TasksConfig,
+    pub ui: UiConfig,
+    pub goal: GoalConfig,
+    pub daemon: DaemonConfig,
+    pub hooks: HooksConfig,
+    /// Named rhythms to switch to at runtime, besides the `[timer]` one
+    /// called `default`.
+    pub presets: BTreeMap<String, PresetConfig>,
+    /// Extra keys for palette actions. They take precedence over the
+    /// built-in keys outside of text inputs.
+    pub keys: HashMap<PaletteAction, Key>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimerConfig {
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub focus: Duration,
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub short_break: Duration,
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub long_break: Duration,
+    /// Completed focus blocks before a long break, 0 for no long breaks.
+    pub long_break_every: u32,
+    /// Pauses the timer when it switches between focus and rest.
+    pub autopause: bool,
+    /// What to do with the timer left running by the last run.
+    pub resume: Resume,
+    /// What the computer sleeping while the timer runs does to it.
+    pub on_suspend: SuspendHandling,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resume {
+    /// Asks in the TUI. `tomatoesd` has no one to ask, so it resumes.
+    #[default]
+    Ask,
+    Always,
+    /// Always starts with a new focus block.
+    Never,
+}
+
+/// A preset in `[presets.<name>]`. The long break settings fall back to the
+/// `[timer]` ones.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PresetConfig {
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub focus: Duration,
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub short_break: Duration,
+    #[serde(default, deserialize_with = "duration_option_deserialize")]
+    pub long_break: Option<Duration>,
+    #[serde(default)]
+    pub long_break_every: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlarmConfig {
+    pub enabled: bool,
+    pub sound: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TasksConfig {
+    pub auto_complete_parent: bool,
+    /// Days before done tasks go to the archive, 0 to keep them.
+    pub archive_after_days: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Time after a space press during which more presses are ignored.
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub space_debounce: Duration,
+    /// Time without a key during focus before the TUI asks whether anyone
+    /// is still working. Unset, it never asks.
+    #[serde(deserialize_with = "duration_option_deserialize")]
+    pub idle_after: Option<Duration>,
+}
+
+/// The daily goal, either a number of pomodoros or a focus time. Unset, it
+/// is 8 pomodoros.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GoalConfig {
+    pub pomodoros: Option<u32>,
+    #[serde(deserialize_with = "duration_option_deserialize")]
+    pub focus: Option<Duration>,
+}
+
+impl GoalConfig {
+    pub fn goal(&self) -> DailyGoal {
+        match (self.pomodoros, self.focus) {
+            (_, Some(focus)) => DailyGoal::Focus(focus),
+            (Some(pomodoros), None) => DailyGoal::Pomodoros(pomodoros),
+            (None, None) => DailyGoal::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// Where `tomatoesd` listens, instead of the one `Config::socket_path`
+    /// picks.
+    pub socket: Option<PathBuf>,
+}
+
+/// Shell commands run on timer and task events, told about the event in
+/// `TOMATOES_*` variables and as JSON on stdin.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub focus_start: Option<String>,
+    pub focus_end: Option<String>,
+    pub break_start: Option<String>,
+    pub break_end: Option<String>,
+    pub task_done: Option<String>,
+    /// How long a hook may run before it is killed.
+    #[serde(deserialize_with = "duration_deserialize")]
+    pub timeout: Duration,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            focus_start: None,
+            focus_end: None,
+            break_start: None,
+            break_end: None,
+            task_done: None,
+            timeout: HOOK_TIMEOUT,
+        }
+    }
+}
+
+impl HooksConfig {
+    pub fn hooks(&self) -> Hooks {
+        let mut hooks = Hooks::new(self.timeout);
+        let commands = [
+            (HookEvent::FocusStart, &self.focus_start),
+            (HookEvent::FocusEnd, &self.focus_end),
+            (HookEvent::BreakStart, &self.break_start),
+            (HookEvent::BreakEnd, &self.break_end),
+            (HookEvent::TaskDone, &self.task_done),
+        ];
+        for (event, command) in commands {
+            if let Some(command) = command {
+                hooks.set(event, command.as_str());
+            }
+        }
+        hooks
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::from(DATA_DIR),
+            timer: TimerConfig::default(),
+            alarm: AlarmConfig::default(),
+            tasks: TasksConfig::default(),
+            ui: UiConfig::default(),
+            goal: GoalConfig::default(),
+            daemon: DaemonConfig::default(),
+            hooks: HooksConfig::default(),
+            presets: BTreeMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            focus: Duration::from_secs(25 * 60),
+            short_break: Duration::from_secs(5 * 60),
+            long_break: Duration::from_secs(15 * 60),
+            long_break_every: 4,
+            autopause: true,
+            resume: Resume::default(),
+            on_suspend: SuspendHandling::default(),
+        }
+    }
+}
+
+impl Default for AlarmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sound: PathBuf::from(ALARM_SOUND),
+        }
+    }
+}
+
+impl Default for TasksConfig {
+    fn default() -> Self {
+        Self {
+            auto_complete_parent: true,
+            archive_after_days: 7,
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            space_debounce: Duration::from_secs(2),
+            idle_after: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    Invalid {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            ConfigError::Parse { path, message } => {
+                write!(f, "{}: {}", path.display(), message.trim_end())
+            }
+            ConfigError::Invalid { path, message } => write!(f, "{}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/tomatoes/config.toml`, or under `~/.config` when the
+    /// variable isn't set.
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_home.join("tomatoes").join("config.toml"))
+    }
+
+    /// Reads the config at `path`, or at `default_path` when it's `None`. A
+    /// missing default config gives the defaults; a missing `path` is an
+    /// error.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content, &path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => Ok(Self::default()),
+            Err(error) => Err(ConfigError::Io { path, error }),
+        }
+    }
+
+    /// Parses and validates a config. `path` only names the file in errors.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(content).map_err(|e| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        config.validate().map_err(|message| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            message,
+        })?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (name, duration) in [
+            ("timer.focus", self.timer.focus),
+            ("timer.short_break", self.timer.short_break),
+            ("timer.long_break", self.timer.long_break),
+        ] {
+            if duration < Duration::from_secs(1) {
+                return Err(format!("{name} has to be at least one second"));
+            }
+        }
+
+        if self.presets.contains_key(DEFAULT_PRESET) {
+            return Err(format!(
+                "presets.{DEFAULT_PRESET} is taken by the [timer] settings"
+            ));
+        }
+        for (name, preset) in self.presets.iter() {
+            let durations = [
+                ("focus", Some(preset.focus)),
+                ("short_break", Some(preset.short_break)),
+                ("long_break", preset.long_break),
+            ];
+            for (field, duration) in durations {
+                if duration.is_some_and(|duration| duration < Duration::from_secs(1)) {
+                    return Err(format!(
+                        "presets.{name}.{field} has to be at least one second"
+                    ));
+                }
+            }
+        }
+
+        match (self.goal.pomodoros, self.goal.focus) {
+            (Some(_), Some(_)) => {
+                return Err("goal.pomodoros and goal.focus can't both be set".to_string());
+            }
+            (Some(0), None) => return Err("goal.pomodoros has to be at least 1".to_string()),
+            (None, Some(focus)) if focus < Duration::from_secs(1) => {
+                return Err("goal.focus has to be at least one second".to_string());
+            }
+            _ => {}
+        }
+
+        if self.data_dir.as_os_str().is_empty() {
+            return Err("data_dir can't be empty".to_string());
+        }
+
+        // The bundled sound is looked up from where the app runs, only a
+        // sound set here can be checked up front.
+        let sound = home_expand(&self.alarm.sound);
+        if self.alarm.enabled && self.alarm.sound != Path::new(ALARM_SOUND) && !sound.is_file() {
+            return Err(format!("alarm.sound: no sound file at {}", sound.display()));
+        }
+
+        let mut bindings: Vec<(&PaletteAction, &Key)> = self.keys.iter().collect();
+        bindings.sort_by_key(|(action, _)| **action);
+        for (i, (action, key)) in bindings.iter().enumerate() {
+            if let Some((other, _)) = bindings[..i].iter().find(|(_, other)| other == key) {
+                return Err(format!(
+                    "keys.{} and keys.{} are both bound to `{key}`",
+                    other.name(),
+                    action.name()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The data dir, with a leading `~` expanded to the home dir.
+    pub fn data_dir(&self) -> PathBuf {
+        home_expand(&self.data_dir)
+    }
+
+    /// The `tomatoesd` socket: `daemon.socket`, else `tomatoes.sock` in
+    /// `$XDG_RUNTIME_DIR`, else a per user one in the temp dir.
+    pub fn socket_path(&self) -> PathBuf {
+        if let Some(socket) = &self.daemon.socket {
+            return home_expand(socket);
+        }
+        match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("tomatoes.sock"),
+            _ => {
+                let user = std::env::var("USER").unwrap_or_default();
+                std::env::temp_dir().join(format!("tomatoes-{user}.sock"))
+            }
+        }
+    }
+
+    /// The `[timer]` settings as the `default` preset, then the named ones.
+    pub fn presets(&self) -> Vec<Preset> {
+        let default = Preset {
+            name: DEFAULT_PRESET.to_string(),
+            focus: self.timer.focus,
+            rest: self.timer.short_break,
+            long_rest: self.timer.long_break,
+            long_rest_every: self.timer.long_break_every,
+        };
+        let named = self.presets.iter().map(|(name, preset)| Preset {
+            name: name.clone(),
+            focus: preset.focus,
+            rest: preset.short_break,
+            long_rest: preset.long_break.unwrap_or(self.timer.long_break),
+            long_rest_every: preset
+                .long_break_every
+                .unwrap_or(self.timer.long_break_every),
+        });
+
+        std::iter::once(default).chain(named).collect()
+    }
+
+    /// A pomodoro set up with the durations and behaviour of this config.
+    pub fn pomodoro(&self) -> Pomodoro {
+        let mut pomodoro = Pomodoro::new(self.timer.focus, self.timer.short_break);
+        pomodoro.long_rest_set(self.timer.long_break, self.timer.long_break_every);
+        pomodoro.presets_set(self.presets());
+        pomodoro.data_dir_set(self.data_dir());
+        pomodoro.alarm_sound_set(home_expand(&self.alarm.sound));
+        if !self.alarm.enabled {
+            pomodoro.alarm_disable();
+        }
+        pomodoro.auto_complete_parent_set(self.tasks.auto_complete_parent);
+        pomodoro.goal_set(self.goal.goal());
+        pomodoro.hooks_set(self.hooks.hooks());
+        pomodoro
+            .archive_after_days_set(Some(self.tasks.archive_after_days).filter(|days| *days > 0));
+        pomodoro
+    }
+}
+
+/// `path` with a leading `~` expanded to the home dir.
+fn home_expand(path: &Path) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Parses durations like `25m`, `90s`, `1h30m` or `1h 30m`. A bare number is
+/// taken as minutes.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("invalid duration `{s}`, expected something like `25m` or `1h30m`");
+    if let Ok(minutes) = s.parse::<u64>() {
+        return minutes
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid);
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' | 's' => {
+                let value: u64 = number.parse().map_err(|_| invalid())?;
+                let secs = match c {
+                    'h' => value.checked_mul(60 * 60),
+                    'm' => value.checked_mul(60),
+                    _ => Some(value),
+                };
+                total = secs
+                    .and_then(|secs| total.checked_add(secs))
+                    .ok_or_else(invalid)?;
+                number.clear();
+            }
+            ' ' if number.is_empty() => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    if s.is_empty() || !number.is_empty() {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Formats a duration the way `parse_duration` reads it, like `1h30m`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    let mut formatted = String::new();
+    if hours > 0 {
+        formatted += &format!("{hours}h");
+    }
+    if mins > 0 {
+        formatted += &format!("{mins}m");
+    }
+    if secs > 0 || formatted.is_empty() {
+        formatted += &format!("{secs}s");
+    }
+    formatted
+}
+
+/// Durations in the config are strings like `25m`, or whole minutes.
+fn duration_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Minutes(u64),
+        Text(String),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Minutes(minutes) => {
+            parse_duration(&minutes.to_string()).map_err(serde::de::Error::custom)
+        }
+        Raw::Text(text) => parse_duration(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+fn duration_option_deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    duration_deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Config, ConfigError> {
+        Config::parse(content, Path::new("config.toml"))
+    }
+
+    mod parse_duration {
+        use super::*;
+
+        #[test]
+        fn should_parse_units() {
+            assert_eq!(parse_duration("25m"), Ok(Duration::from_secs(25 * 60)));
+            assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+            assert_eq!(parse_duration("1h 30m"), Ok(Duration::from_secs(90 * 60)));
+            assert_eq!(parse_duration("1h30m15s"), Ok(Duration::from_secs(5415)));
+            assert_eq!(parse_duration("5"), Ok(Duration::from_secs(5 * 60)));
+        }
+
+        #[test]
+        fn should_reject_invalid_durations() {
+            for s in [
+                "",
+                "m",
+                "25x",
+                "1h30",
+                "-5m",
+                "1.5h",
+                "18446744073709551615",
+                "5124095576030432h",
+                "18446744073709551615s 1s",
+            ] {
+                assert!(parse_duration(s).is_err(), "{s}");
+            }
+        }
+    }
+
+    mod format_duration {
+        use super::*;
+
+        #[test]
+        fn should_format_as_parsed() {
+            for s in ["25m", "1h30m", "1h", "1m30s", "0s"] {
+                assert_eq!(format_duration(parse_duration(s).unwrap()), s);
+            }
+            assert_eq!(format_duration(Duration::from_secs(90)), "1m30s");
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_default_missing_fields() {
+            let config = parse("").unwrap();
+
+            assert_eq!(config, Config::default());
+        }
+
+        #[test]
+        fn should_read_every_section() {
+            let config = parse(
+                r#"
+                data_dir = "/tmp/tomatoes"
+
+                [timer]
+                focus = "50m"
+                short_break = 10
+                long_break = "30m"
+                long_break_every = 3
+                autopause = false
+                resume = "never"
+                on_suspend = "void"
+
+                [alarm]
+                enabled = false
+
+                [tasks]
+                archive_after_days = 0
+
+                [ui]
+                space_debounce = "0s"
+                idle_after = "10m"
+
+                [goal]
+                focus = "4h"
+
+                [daemon]
+                socket = "/tmp/tomatoes-test.sock"
+
+                [hooks]
+                focus_start = "dnd on"
+                timeout = "3s"
+
+                [keys]
+                undo = "z"
+                palette_open = "ctrl-k"
+                "#,
+            )
+            .unwrap();
+
+            assert_eq!(config.data_dir, PathBuf::from("/tmp/tomatoes"));
+            assert_eq!(config.timer.focus, Duration::from_secs(50 * 60));
+            assert_eq!(config.timer.short_break, Duration::from_secs(10 * 60));
+            assert_eq!(config.timer.long_break_every, 3);
+            assert!(!config.timer.autopause);
+            assert_eq!(config.timer.resume, Resume::Never);
+            assert_eq!(config.timer.on_suspend, SuspendHandling::Void);
+            assert!(!config.alarm.enabled);
+            assert_eq!(config.alarm.sound, PathBuf::from(ALARM_SOUND));
+            assert_eq!(config.tasks.archive_after_days, 0);
+            assert_eq!(config.ui.space_debounce, Duration::ZERO);
+            assert_eq!(config.ui.idle_after, Some(Duration::from_secs(600)));
+            assert_eq!(
+                config.goal.goal(),
+                DailyGoal::Focus(Duration::from_secs(4 * 60 * 60))
+            );
+            assert_eq!(
+                config.socket_path(),
+                PathBuf::from("/tmp/tomatoes-test.sock")
+            );
+            assert_eq!(config.hooks.focus_start, Some("dnd on".to_string()));
+            assert_eq!(config.hooks.focus_end, None);
+            assert_eq!(config.hooks.timeout, Duration::from_secs(3));
+            assert_eq!(
+                config.keys.get(&PaletteAction::Undo),
+                Key::parse("z").ok().as_ref()
+            );
+            assert_eq!(
+                config.keys.get(&PaletteAction::PaletteOpen),
+                Key::parse("ctrl-k").ok().as_ref()
+            );
+        }
+
+        #[test]
+        fn should_explain_bad_values() {
+            let error = parse("[timer]\nfocus = \"25 minutes\"").unwrap_err();
+            assert!(matches!(error, ConfigError::Parse { .. }));
+            assert!(error.to_string().starts_with("config.toml: "));
+            assert!(error.to_string().contains("invalid duration `25 minutes`"));
+
+            let error = parse("[timer]\nfocus = 307445734561825861").unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("invalid duration `307445734561825861`"));
+
+            let error = parse("[timer]\nfocus = \"0m\"").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "config.toml: timer.focus has to be at least one second"
+            );
+
+            let error = parse("[alarm]\nsound = \"/nowhere/bell.mp3\"").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "config.toml: alarm.sound: no sound file at /nowhere/bell.mp3"
+            );
+
+            let error = parse("[goal]\npomodoros = 6\nfocus = \"4h\"").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "config.toml: goal.pomodoros and goal.focus can't both be set"
+            );
+
+            let error = parse("[keys]\nfly = \"f\"").unwrap_err();
+            assert!(error.to_string().contains("unknown variant `fly`"));
+
+            let error = parse("[keys]\nundo = \"hyper-z\"").unwrap_err();
+            assert!(error.to_string().contains("unknown key `hyper-z`"));
+
+            let error = parse("[timer]\nfocs = \"25m\"").unwrap_err();
+            assert!(error.to_string().contains("unknown field `focs`"));
+        }
+
+        #[test]
+        fn should_read_presets() {
+            let config = parse(
+                r#"
+                [timer]
+                long_break = "20m"
+
+                [presets.deep]
+                focus = "50m"
+                short_break = "10m"
+                long_break_every = 2
+
+                [presets.email]
+                focus = 15
+                short_break = 3
+                long_break = "5m"
+                "#,
+            )
+            .unwrap();
+
+            let presets = config.presets();
+            let names: Vec<&str> = presets.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(names, vec![DEFAULT_PRESET, "deep", "email"]);
+            assert_eq!(presets[1].focus, Duration::from_secs(50 * 60));
+            assert_eq!(presets[1].long_rest, Duration::from_secs(20 * 60));
+            assert_eq!(presets[1].long_rest_every, 2);
+            assert_eq!(presets[2].rest, Duration::from_secs(3 * 60));
+            assert_eq!(presets[2].long_rest, Duration::from_secs(5 * 60));
+            assert_eq!(presets[2].long_rest_every, 4);
+        }
+
+        #[test]
+        fn should_reject_bad_presets() {
+            let error = parse("[presets.deep]\nfocus = \"50m\"").unwrap_err();
+            assert!(error.to_string().contains("missing field `short_break`"));
+
+            let error = parse("[presets.deep]\nfocus = \"50m\"\nshort_break = \"0s\"").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "config.toml: presets.deep.short_break has to be at least one second"
+            );
+
+            let error =
+                parse("[presets.default]\nfocus = \"50m\"\nshort_break = \"5m\"").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "config.toml: presets.default is taken by the [timer] settings"
+            );
+        }
+
+        #[test]
+        fn should_reject_keys_bound_twice() {
+            let error = parse("[keys]\nundo = \"z\"\nredo = \"z\"").unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                "config.toml: keys.undo and keys.redo are both bound to `z`"
+            );
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn should_fail_when_the_given_file_is_missing() {
+            let error = Config::load(Some(Path::new("/nonexistent/config.toml"))).unwrap_err();
+
+            assert!(matches!(error, ConfigError::Io { .. }));
+        }
+    }
+
+    mod pomodoro {
+        use super::*;
+
+        #[test]
+        fn should_apply_the_settings() {
+            let config = Config {
+                data_dir: PathBuf::from("/tmp/tomatoes"),
+                tasks: TasksConfig {
+                    auto_complete_parent: false,
+                    archive_after_days: 7,
+                },
+                goal: GoalConfig {
+                    pomodoros: Some(6),
+                    focus: None,
+                },
+                ..Config::default()
+            };
+
+            let pomodoro = config.pomodoro();
+
+            assert_eq!(
+                pomodoro.get_current_timer().initial_time,
+                config.timer.focus
+            );
+            assert_eq!(pomodoro.data_dir_get(), Path::new("/tmp/tomatoes"));
+            assert!(!pomodoro.auto_complete_parent_get());
+            assert_eq!(pomodoro.goal_get(), DailyGoal::Pomodoros(6));
+        }
+    }
+}
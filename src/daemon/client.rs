@@ -0,0 +1,62 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use super::protocol::{Event, Request, Response, Status};
+
+/// A connection to `tomatoesd`.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    pub fn connect(path: &Path) -> io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+
+    /// Sends `request` and waits for its answer. Failed requests come back
+    /// as `Ok` with `ok: false`.
+    pub fn request(&mut self, request: &Request) -> io::Result<Response> {
+        writeln!(self.writer, "{}", serde_json::to_string(request)?)?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the daemon closed the connection",
+            ));
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Turns the connection into a stream of events, read on their own
+    /// thread. The channel closes when the daemon goes away.
+    pub fn subscribe(mut self) -> io::Result<(Status, Receiver<Event>)> {
+        let response = self.request(&Request::Subscribe)?;
+        let status = match response.status {
+            Some(status) => status,
+            None => {
+                let message = response.error.unwrap_or_default();
+                return Err(io::Error::other(message));
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // Answers to requests sent on this connection are skipped.
+            for line in self.reader.lines().map_while(Result::ok) {
+                if let Ok(event) = serde_json::from_str::<Event>(&line) {
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok((status, receiver))
+    }
+}
@@ -0,0 +1,11 @@
+//! `tomatoesd`, a background process that owns the pomodoro, and the
+//! line-delimited JSON protocol its clients speak over a Unix socket. The
+//! protocol is described in the README.
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::*;
+pub use protocol::*;
+pub use server::*;
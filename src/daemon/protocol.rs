@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{PhaseChange, Pomodoro, Suspend, Task, Timer, TimerType};
+
+/// A line sent to `tomatoesd`, tagged by `cmd`:
+/// `{"cmd":"task_done","id":4}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    Status,
+    Start,
+    Pause,
+    /// Starts a paused timer and pauses a running one.
+    Toggle,
+    Skip,
+    Reset,
+    /// Switches presets. Without `change`, a phase that already started
+    /// keeps its time left and one that didn't starts over.
+    Preset {
+        name: String,
+        #[serde(default)]
+        change: Option<PhaseChange>,
+    },
+    /// Adds a task written as in the TUI: `Write report +work ~2`.
+    TaskAdd {
+        text: String,
+    },
+    TaskList {
+        #[serde(default)]
+        done: bool,
+    },
+    TaskDone {
+        id: u64,
+    },
+    TaskRemove {
+        id: u64,
+    },
+    /// Reads the tasks again, after another program changed the files.
+    Reload,
+    /// Sends every `Event` to this connection from now on.
+    Subscribe,
+}
+
+/// The line answering a request. Only the fields that apply are sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<Vec<Task>>,
+}
+
+impl Response {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            error: Some(message.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_status(status: Status) -> Self {
+        Self {
+            status: Some(status),
+            ..Self::ok()
+        }
+    }
+}
+
+/// A line pushed to subscribed connections, tagged by `event`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Sent every second, and right after the timer is started, paused or
+    /// changed.
+    Tick { status: Status },
+    /// The timer switched between focus and rest.
+    Phase { status: Status },
+    /// The computer slept while the timer ran, and the daemon handled it
+    /// as the config says.
+    Suspended { suspend: Suspend, status: Status },
+    /// The tasks changed.
+    Tasks,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    Focus,
+    Rest,
+}
+
+impl From<&TimerType> for Mode {
+    fn from(timer_type: &TimerType) -> Self {
+        match timer_type {
+            TimerType::Focus => Mode::Focus,
+            TimerType::Rest => Mode::Rest,
+            TimerType::Transitioning(next) => Mode::from(next.as_ref()),
+        }
+    }
+}
+
+impl From<Mode> for TimerType {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Focus => TimerType::Focus,
+            Mode::Rest => TimerType::Rest,
+        }
+    }
+}
+
+/// The timer as the daemon runs it. Times are in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub mode: Mode,
+    pub paused: bool,
+    pub remaining: u64,
+    pub duration: u64,
+    pub long_rest: bool,
+    pub preset: String,
+    /// Today's progress, like `3/8`.
+    pub goal: String,
+    pub task: Option<Task>,
+}
+
+impl Status {
+    pub fn new(pomodoro: &Pomodoro, paused: bool) -> Self {
+        let mode = Mode::from(&pomodoro.get_mode());
+        let timer = pomodoro.get_current_timer();
+        Self {
+            mode,
+            paused,
+            remaining: timer.current_time.as_secs(),
+            duration: timer.initial_time.as_secs(),
+            long_rest: mode == Mode::Rest && pomodoro.rest_is_long(),
+            preset: pomodoro.preset_get().name.clone(),
+            goal: pomodoro.goal_progress(),
+            task: pomodoro.task_active_get(),
+        }
+    }
+
+    pub fn timer(&self) -> Timer {
+        Timer {
+            current_time: Duration::from_secs(self.remaining),
+            initial_time: Duration::from_secs(self.duration),
+            elapsed_mark: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod request {
+        use super::*;
+
+        #[test]
+        fn should_read_commands_by_name() {
+            let request: Request = serde_json::from_str(r#"{"cmd":"task_done","id":4}"#).unwrap();
+            assert_eq!(request, Request::TaskDone { id: 4 });
+
+            let request: Request = serde_json::from_str(r#"{"cmd":"task_list"}"#).unwrap();
+            assert_eq!(request, Request::TaskList { done: false });
+
+            let request: Request =
+                serde_json::from_str(r#"{"cmd":"preset","name":"deep","change":"scale"}"#).unwrap();
+            assert_eq!(
+                request,
+                Request::Preset {
+                    name: "deep".to_string(),
+                    change: Some(PhaseChange::Scale)
+                }
+            );
+
+            assert!(serde_json::from_str::<Request>(r#"{"cmd":"explode"}"#).is_err());
+        }
+    }
+
+    mod response {
+        use super::*;
+
+        #[test]
+        fn should_leave_out_empty_fields() {
+            assert_eq!(
+                serde_json::to_string(&Response::ok()).unwrap(),
+                r#"{"ok":true}"#
+            );
+            assert_eq!(
+                serde_json::to_string(&Response::error("no task #4")).unwrap(),
+                r#"{"ok":false,"error":"no task #4"}"#
+            );
+        }
+    }
+
+    mod status {
+        use super::*;
+
+        #[test]
+        fn should_describe_the_timer() {
+            let pomodoro = Pomodoro::new(Duration::from_secs(25 * 60), Duration::from_secs(300));
+
+            let status = Status::new(&pomodoro, true);
+
+            assert_eq!(status.mode, Mode::Focus);
+            assert!(status.paused);
+            assert_eq!(status.remaining, 25 * 60);
+            assert_eq!(status.timer(), pomodoro.get_current_timer());
+            assert_eq!(
+                serde_json::to_value(&status).unwrap()["mode"],
+                serde_json::json!("focus")
+            );
+        }
+    }
+}
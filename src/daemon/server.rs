@@ -0,0 +1,546 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+
+use crate::backend::{
+    sound_play, PhaseChange, Pomodoro, SleepWatch, SuspendHandling, Task, SNAPSHOT_INTERVAL,
+};
+use crate::config::{Config, Resume};
+
+use super::protocol::{Event, Request, Response, Status};
+
+/// How long a subscriber may take to read an event before it's dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Lines a subscriber may fall behind by before it's dropped.
+const SUBSCRIBER_QUEUE: usize = 64;
+
+/// Owns the pomodoro and runs its timer for the clients of a Unix socket.
+pub struct Daemon {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    pomodoro: Pomodoro,
+    paused: bool,
+    autopause: bool,
+    on_suspend: SuspendHandling,
+    alarm: Option<PathBuf>,
+    /// Queues of the subscribed connections, each written to by its own
+    /// thread so no one writes to a socket while holding the state.
+    subscribers: Vec<SyncSender<String>>,
+    /// Seconds run since the timer was last saved.
+    unsaved: Duration,
+}
+
+impl Daemon {
+    /// Picks up the timer left by the last run unless `timer.resume` is
+    /// `never`. Otherwise it starts paused, waiting for a `start`.
+    pub fn new(mut pomodoro: Pomodoro, config: &Config) -> Self {
+        // The alarm is played here, on its own thread, so it doesn't hold
+        // the clients up the way `Pomodoro::forward` would.
+        let alarm = pomodoro
+            .alarm_get()
+            .then(|| pomodoro.alarm_sound_get().to_path_buf());
+        pomodoro.alarm_disable();
+
+        let autopause = config.timer.autopause;
+        let mut paused = true;
+        if config.timer.resume != Resume::Never {
+            match pomodoro.snapshot_load() {
+                Ok(Some(snapshot)) => paused = pomodoro.resume(&snapshot, Local::now()),
+                Ok(None) => {}
+                Err(e) => eprintln!("tomatoesd: could not read the saved timer: {e}"),
+            }
+        }
+
+        let mut state = State {
+            pomodoro,
+            paused,
+            autopause,
+            on_suspend: config.timer.on_suspend,
+            alarm,
+            subscribers: vec![],
+            unsaved: Duration::ZERO,
+        };
+        // Sessions that ended while nothing ran.
+        state.history_save();
+        state.snapshot_save();
+
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Listens on `path`, replacing a socket left behind by a daemon that
+    /// is gone. Fails when another daemon answers there.
+    pub fn bind(path: &Path) -> io::Result<UnixListener> {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", path.display()),
+            ));
+        }
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        UnixListener::bind(path)
+    }
+
+    /// Runs the timer and serves each client on its own thread. Only returns
+    /// when the listener fails.
+    pub fn run(self, listener: UnixListener) -> io::Result<()> {
+        let state = Arc::clone(&self.state);
+        thread::spawn(move || {
+            let mut next = Instant::now();
+            let mut sleep_watch = SleepWatch::default();
+            loop {
+                next += Duration::from_secs(1);
+                thread::sleep(next.saturating_duration_since(Instant::now()));
+                let mut state = lock(&state);
+                if let Some((from, to)) = sleep_watch.check() {
+                    state.suspended(from, to);
+                }
+                state.tick();
+            }
+        });
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let state = Arc::clone(&self.state);
+            thread::spawn(move || {
+                if let Err(e) = serve(&state, stream) {
+                    eprintln!("tomatoesd: client: {e}");
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A client panicking shouldn't take the timer down with it.
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Answers the requests of one connection, a line each, until it closes.
+fn serve(state: &Mutex<State>, stream: UnixStream) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Subscribe) => match lock(state).subscribe(&writer) {
+                Ok(()) => continue,
+                Err(e) => Response::error(e.to_string()),
+            },
+            Ok(request) => lock(state).handle(request),
+            Err(e) => Response::error(format!("bad request: {e}")),
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    }
+    Ok(())
+}
+
+impl State {
+    fn status(&self) -> Status {
+        Status::new(&self.pomodoro, self.paused)
+    }
+
+    fn handle(&mut self, request: Request) -> Response {
+        match request {
+            Request::Status => Response::with_status(self.status()),
+            Request::Start => self.pause_set(false),
+            Request::Pause => self.pause_set(true),
+            Request::Toggle => self.pause_set(!self.paused),
+            Request::Skip => {
+                self.pomodoro.next_mode();
+                self.phase_end();
+                Response::with_status(self.status())
+            }
+            Request::Reset => {
+                self.pomodoro.reset_timer(self.pomodoro.get_mode());
+                self.history_save();
+                self.snapshot_save();
+                self.tick_send();
+                Response::with_status(self.status())
+            }
+            Request::Preset { name, change } => {
+                let timer = self.pomodoro.get_current_timer();
+                let change = change.unwrap_or(if timer.elapsed().is_zero() {
+                    PhaseChange::Restart
+                } else {
+                    PhaseChange::Keep
+                });
+                if !self.pomodoro.preset_apply(&name, change) {
+                    return Response::error(format!("no preset `{name}`"));
+                }
+                self.snapshot_save();
+                self.tick_send();
+                Response::with_status(self.status())
+            }
+            Request::TaskAdd { text } => {
+                let task = Task::from_str(&text);
+                self.pomodoro.task_add(task.clone());
+                self.tasks_changed(Response {
+                    task: Some(task),
+                    ..Response::ok()
+                })
+            }
+            Request::TaskList { done } => Response {
+                tasks: Some(self.pomodoro.task_get_by_complete(done)),
+                ..Response::ok()
+            },
+            Request::TaskDone { id } => match self.pomodoro.task_position(id) {
+                Some((false, index)) => {
+                    self.pomodoro.task_complete(index);
+                    self.tasks_changed(Response {
+                        task: self.pomodoro.task_get(id),
+                        ..Response::ok()
+                    })
+                }
+                Some((true, _)) => Response {
+                    task: self.pomodoro.task_get(id),
+                    ..Response::ok()
+                },
+                None => Response::error(format!("no task #{id}")),
+            },
+            Request::TaskRemove { id } => match self.pomodoro.task_get(id) {
+                Some(task) => {
+                    self.pomodoro.task_remove_by_attributes(task.clone());
+                    self.tasks_changed(Response {
+                        task: Some(task),
+                        ..Response::ok()
+                    })
+                }
+                None => Response::error(format!("no task #{id}")),
+            },
+            Request::Reload => match self.pomodoro.tasks_load() {
+                Ok(()) => {
+                    self.broadcast(&Event::Tasks);
+                    Response::ok()
+                }
+                Err(e) => Response::error(format!("could not read the tasks: {e}")),
+            },
+            // `serve` hands these to `subscribe`, which needs the connection.
+            Request::Subscribe => Response::error("subscribe needs a connection"),
+        }
+    }
+
+    /// Sends every event to `client` from now on. Its answer goes first in
+    /// the same queue, so no event can get ahead of it.
+    fn subscribe(&mut self, client: &UnixStream) -> io::Result<()> {
+        let stream = client.try_clone()?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let line = serde_json::to_string(&Response::with_status(self.status()))?;
+
+        let (sender, receiver) = mpsc::sync_channel(SUBSCRIBER_QUEUE);
+        let _ = sender.try_send(line + "\n");
+        thread::spawn(move || subscriber_write(stream, receiver));
+        self.subscribers.push(sender);
+        Ok(())
+    }
+
+    /// Counts a second down, unless paused, and tells the subscribers.
+    fn tick(&mut self) {
+        if self.paused {
+            self.tick_send();
+            return;
+        }
+
+        let mode = self.pomodoro.get_mode();
+        self.pomodoro.forward();
+        if self.pomodoro.get_mode() == mode {
+            self.unsaved += Duration::from_secs(1);
+            if self.unsaved >= SNAPSHOT_INTERVAL {
+                self.snapshot_save();
+            }
+            self.tick_send();
+            return;
+        }
+
+        if let Some(sound) = self.alarm.clone() {
+            thread::spawn(move || {
+                if let Err(e) = sound_play(&sound) {
+                    eprintln!("tomatoesd: alarm: {e}");
+                }
+            });
+        }
+        self.phase_end();
+    }
+
+    fn pause_set(&mut self, paused: bool) -> Response {
+        self.paused = paused;
+        self.snapshot_save();
+        self.tick_send();
+        Response::with_status(self.status())
+    }
+
+    /// Deals with the computer having slept from `from` to `to`. A paused
+    /// timer has nothing to make up for.
+    fn suspended(&mut self, from: DateTime<Local>, to: DateTime<Local>) {
+        if self.paused {
+            return;
+        }
+        self.paused = self.pomodoro.suspend(from, to, self.on_suspend);
+        self.history_save();
+        self.snapshot_save();
+        if let Some(suspend) = self.pomodoro.history_get().suspends.last().cloned() {
+            let status = self.status();
+            self.broadcast(&Event::Suspended { suspend, status });
+        }
+    }
+
+    /// Saves the session that just ended and announces the new phase.
+    fn phase_end(&mut self) {
+        if self.autopause {
+            self.paused = true;
+        }
+        self.history_save();
+        self.snapshot_save();
+        self.broadcast(&Event::Phase {
+            status: self.status(),
+        });
+    }
+
+    fn history_save(&self) {
+        if let Err(e) = self.pomodoro.history_save() {
+            eprintln!("tomatoesd: could not save the history: {e}");
+        }
+    }
+
+    fn snapshot_save(&mut self) {
+        self.unsaved = Duration::ZERO;
+        if let Err(e) = self.pomodoro.snapshot_save(self.paused) {
+            eprintln!("tomatoesd: could not save the timer: {e}");
+        }
+    }
+
+    fn tasks_changed(&mut self, response: Response) -> Response {
+        // Subscribers reread the file, so it has to be written first.
+        match self.pomodoro.tasks_save() {
+            Ok(()) => {
+                self.broadcast(&Event::Tasks);
+                response
+            }
+            Err(e) => Response::error(format!("could not save the tasks: {e}")),
+        }
+    }
+
+    fn tick_send(&mut self) {
+        self.broadcast(&Event::Tick {
+            status: self.status(),
+        });
+    }
+
+    /// Sends `event` to every subscriber, dropping the ones that are gone.
+    fn broadcast(&mut self, event: &Event) {
+        let line = match serde_json::to_string(event) {
+            Ok(line) => line + "\n",
+            Err(_) => return,
+        };
+        self.subscribers
+            .retain(|subscriber| subscriber.try_send(line.clone()).is_ok());
+    }
+}
+
+/// Writes the queued lines to a subscriber until it's gone or dropped.
+fn subscriber_write(mut stream: UnixStream, lines: Receiver<String>) {
+    for line in lines {
+        if stream.write_all(line.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::daemon::protocol::Mode;
+
+    fn state() -> State {
+        let dir = std::env::temp_dir().join("tomatoesd-test");
+        let mut pomodoro = Pomodoro::new(Duration::from_secs(2), Duration::from_secs(2));
+        pomodoro.data_dir_set(dir);
+        State {
+            pomodoro,
+            paused: true,
+            autopause: true,
+            on_suspend: SuspendHandling::Pause,
+            alarm: None,
+            subscribers: vec![],
+            unsaved: Duration::ZERO,
+        }
+    }
+
+    /// The lines the subscriber thread wrote to `stream`, as a response
+    /// and the events after it.
+    fn received(stream: &UnixStream) -> (Response, Vec<Event>) {
+        stream
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let mut lines = BufReader::new(stream).lines().map_while(Result::ok);
+        let response = serde_json::from_str(&lines.next().unwrap()).unwrap();
+        let events = lines
+            .map(|line| serde_json::from_str(&line).unwrap())
+            .collect();
+        (response, events)
+    }
+
+    mod handle {
+        use super::*;
+
+        #[test]
+        fn should_start_and_pause() {
+            let mut state = state();
+
+            let response = state.handle(Request::Toggle);
+            assert!(!response.status.unwrap().paused);
+
+            let response = state.handle(Request::Pause);
+            assert!(response.ok);
+            assert!(state.paused);
+        }
+
+        #[test]
+        fn should_report_missing_tasks_and_presets() {
+            let mut state = state();
+
+            let response = state.handle(Request::TaskDone { id: u64::MAX });
+            assert!(!response.ok);
+            assert_eq!(response.error, Some(format!("no task #{}", u64::MAX)));
+
+            let response = state.handle(Request::Preset {
+                name: "deep".to_string(),
+                change: None,
+            });
+            assert_eq!(response.error, Some("no preset `deep`".to_string()));
+        }
+
+        #[test]
+        fn should_list_tasks_by_completion() {
+            let mut state = state();
+            let first = Task::new("First", "");
+            let second = Task::new("Second", "");
+            state.pomodoro.task_add(first.clone());
+            state.pomodoro.task_add(second.clone());
+            state.pomodoro.task_complete(1);
+
+            let response = state.handle(Request::TaskList { done: false });
+            assert_eq!(response.tasks, Some(vec![first]));
+
+            let response = state.handle(Request::TaskList { done: true });
+            assert_eq!(response.tasks.unwrap()[0].id, second.id);
+        }
+
+        #[test]
+        fn should_send_events_to_subscribers() {
+            let mut state = state();
+            let (client, server) = UnixStream::pair().unwrap();
+
+            let status = state.status();
+            state.subscribe(&server).unwrap();
+            state.handle(Request::Start);
+            state.handle(Request::Skip);
+
+            let (response, events) = received(&client);
+            assert_eq!(response.status, Some(status));
+            assert!(matches!(&events[0], Event::Tick { status } if !status.paused));
+            assert!(matches!(
+                &events[1],
+                Event::Phase { status } if status.mode == Mode::Rest && status.paused
+            ));
+        }
+    }
+
+    mod tick {
+        use super::*;
+
+        #[test]
+        fn should_only_count_down_when_running() {
+            let mut state = state();
+
+            state.tick();
+            assert_eq!(state.status().remaining, 2);
+
+            state.paused = false;
+            state.tick();
+            assert_eq!(state.status().remaining, 1);
+        }
+
+        #[test]
+        fn should_pause_after_a_phase_with_autopause() {
+            let mut state = state();
+            state.paused = false;
+
+            state.tick();
+            state.tick();
+
+            assert_eq!(state.status().mode, Mode::Rest);
+            assert!(state.paused);
+            assert_eq!(state.pomodoro.history_get().sessions.len(), 1);
+        }
+
+        #[test]
+        fn should_drop_subscribers_that_are_gone() {
+            let mut state = state();
+            let (client, server) = UnixStream::pair().unwrap();
+            state.subscribe(&server).unwrap();
+            drop(client);
+            drop(server);
+
+            // The subscriber thread notices on its first write.
+            for _ in 0..100 {
+                state.tick();
+                if state.subscribers.is_empty() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            assert!(state.subscribers.is_empty());
+        }
+    }
+
+    mod suspended {
+        use super::*;
+
+        #[test]
+        fn should_handle_sleeps_while_running() {
+            let mut state = state();
+            state.on_suspend = SuspendHandling::Count;
+            let (client, server) = UnixStream::pair().unwrap();
+            state.subscribe(&server).unwrap();
+            let to = Local::now();
+            let from = to - chrono::Duration::seconds(60);
+
+            state.suspended(from, to);
+            assert!(state.pomodoro.history_get().suspends.is_empty());
+
+            state.paused = false;
+            state.suspended(from, to);
+
+            assert_eq!(state.status().mode, Mode::Rest);
+            assert!(state.paused);
+            let (_, events) = received(&client);
+            assert!(matches!(
+                &events[..],
+                [Event::Suspended { suspend, status }]
+                    if suspend.handling == SuspendHandling::Count && status.paused
+            ));
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt::Write;
+
+use serde_json::json;
+
+use crate::daemon::{Mode, Status};
+
+/// The template `--follow` uses when no format is given.
+pub const DEFAULT_TEMPLATE: &str = "{mode} {remaining} {task}";
+
+/// How `tomatoes status --format` writes the daemon's timer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusFormat {
+    /// Colored with tmux `#[fg=...]` styles.
+    Tmux,
+    /// Colored with polybar `%{F...}` tags.
+    Polybar,
+    /// A waybar custom module object, with `text`, `tooltip`, `class` and
+    /// `percentage`.
+    Waybar,
+    Template(Vec<Piece>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// A `{name}` in a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// `Focus`, `Rest` or `Long rest`.
+    Mode,
+    /// `running` or `paused`.
+    State,
+    /// Time left, like `24:13`.
+    Remaining,
+    /// Length of the phase, like `25:00`.
+    Duration,
+    /// How much of the phase is over, from 0 to 100.
+    Percentage,
+    /// The active task, or nothing.
+    Task,
+    Preset,
+    /// Today's goal progress, like `3/8`.
+    Goal,
+}
+
+impl Field {
+    const ALL: [(&'static str, Field); 8] = [
+        ("mode", Field::Mode),
+        ("state", Field::State),
+        ("remaining", Field::Remaining),
+        ("duration", Field::Duration),
+        ("percentage", Field::Percentage),
+        ("task", Field::Task),
+        ("preset", Field::Preset),
+        ("goal", Field::Goal),
+    ];
+
+    fn value(&self, status: &Status) -> String {
+        match self {
+            Field::Mode => mode_label(status).to_string(),
+            Field::State => if status.paused { "paused" } else { "running" }.to_string(),
+            Field::Remaining => status.timer().to_string(),
+            Field::Duration => {
+                let mut timer = status.timer();
+                timer.current_time = timer.initial_time;
+                timer.to_string()
+            }
+            Field::Percentage => percentage(status).to_string(),
+            Field::Task => status
+                .task
+                .as_ref()
+                .map(|task| task.name.clone())
+                .unwrap_or_default(),
+            Field::Preset => status.preset.clone(),
+            Field::Goal => status.goal.clone(),
+        }
+    }
+}
+
+impl StatusFormat {
+    /// `tmux`, `polybar`, `waybar`, or a template like
+    /// `{mode} {remaining} {task}`. Braces are written as `{{` and `}}`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "tmux" => return Ok(StatusFormat::Tmux),
+            "polybar" => return Ok(StatusFormat::Polybar),
+            "waybar" => return Ok(StatusFormat::Waybar),
+            _ => {}
+        }
+
+        let mut pieces = vec![];
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unclosed `{{{name}`")),
+                        }
+                    }
+                    let field = Field::ALL
+                        .iter()
+                        .find(|(field_name, _)| *field_name == name)
+                        .map(|(_, field)| *field)
+                        .ok_or_else(|| {
+                            let names: Vec<&str> = Field::ALL.iter().map(|(n, _)| *n).collect();
+                            format!(
+                                "unknown field `{{{name}}}`, expected one of {}",
+                                names.join(", ")
+                            )
+                        })?;
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                }
+                '}' => return Err("unmatched `}`, write `}}` for a brace".to_string()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+
+        Ok(StatusFormat::Template(pieces))
+    }
+
+    /// One line for `status`, without the newline. The bar formats escape
+    /// their markup characters, so a task named `#1` or `50%` shows as is.
+    pub fn render(&self, status: &Status) -> String {
+        match self {
+            StatusFormat::Tmux => {
+                let summary = summary(status).replace('#', "##");
+                format!("#[fg={}]{summary}#[default]", color(status).0)
+            }
+            StatusFormat::Polybar => {
+                let summary = summary(status).replace('%', "%%");
+                format!("%{{F{}}}{summary}%{{F-}}", color(status).1)
+            }
+            StatusFormat::Waybar => {
+                let mut tooltip = format!(
+                    "{} {} left of {}\nGoal: {}",
+                    mode_label(status),
+                    Field::Remaining.value(status),
+                    Field::Duration.value(status),
+                    status.goal
+                );
+                if let Some(task) = &status.task {
+                    let _ = write!(tooltip, "\nTask: {}", task.name);
+                }
+                let class = if status.paused {
+                    "paused"
+                } else {
+                    match status.mode {
+                        Mode::Focus => "focus",
+                        Mode::Rest => "rest",
+                    }
+                };
+                json!({
+                    "text": summary(status),
+                    "tooltip": tooltip,
+                    "class": class,
+                    "percentage": percentage(status),
+                })
+                .to_string()
+            }
+            StatusFormat::Template(pieces) => {
+                let line: String = pieces
+                    .iter()
+                    .map(|piece| match piece {
+                        Piece::Text(text) => text.clone(),
+                        Piece::Field(field) => field.value(status),
+                    })
+                    .collect();
+                line.trim_end().to_string()
+            }
+        }
+    }
+}
+
+fn mode_label(status: &Status) -> &'static str {
+    match status.mode {
+        Mode::Focus => "Focus",
+        Mode::Rest if status.long_rest => "Long rest",
+        Mode::Rest => "Rest",
+    }
+}
+
+fn percentage(status: &Status) -> u64 {
+    match status.duration {
+        0 => 0,
+        duration => (duration - status.remaining.min(duration)) * 100 / duration,
+    }
+}
+
+/// What the ready-made formats show: `Focus 24:13 · Write report`.
+fn summary(status: &Status) -> String {
+    let mut summary = format!("{} {}", mode_label(status), status.timer());
+    if status.paused {
+        summary += " (paused)";
+    }
+    if let Some(task) = &status.task {
+        let _ = write!(summary, " · {}", task.name);
+    }
+    summary
+}
+
+/// The color name for tmux and its hex code for polybar.
+fn color(status: &Status) -> (&'static str, &'static str) {
+    match (status.paused, status.mode) {
+        (true, _) => ("yellow", "#e5c07b"),
+        (false, Mode::Focus) => ("red", "#e06c75"),
+        (false, Mode::Rest) => ("green", "#98c379"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::Task;
+
+    fn status() -> Status {
+        Status {
+            mode: Mode::Focus,
+            paused: false,
+            remaining: 15 * 60,
+            duration: 25 * 60,
+            long_rest: false,
+            preset: "default".to_string(),
+            goal: "3/8".to_string(),
+            task: Some(Task::new("Write report", "")),
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_read_fields_and_text() {
+            assert_eq!(
+                StatusFormat::parse("🍅 {remaining} {{x}}"),
+                Ok(StatusFormat::Template(vec![
+                    Piece::Text("🍅 ".to_string()),
+                    Piece::Field(Field::Remaining),
+                    Piece::Text(" {x}".to_string()),
+                ]))
+            );
+            assert_eq!(StatusFormat::parse("waybar"), Ok(StatusFormat::Waybar));
+        }
+
+        #[test]
+        fn should_reject_unknown_fields() {
+            let error = StatusFormat::parse("{mode} {left}").unwrap_err();
+            assert!(error.starts_with("unknown field `{left}`"), "{error}");
+
+            assert_eq!(
+                StatusFormat::parse("{mode"),
+                Err("unclosed `{mode`".to_string())
+            );
+            assert!(StatusFormat::parse("mode}").is_err());
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn should_fill_templates() {
+            let format =
+                StatusFormat::parse("{mode} {remaining}/{duration} {percentage}% {task} [{goal}]")
+                    .unwrap();
+
+            assert_eq!(
+                format.render(&status()),
+                "Focus 15:00/25:00 40% Write report [3/8]"
+            );
+        }
+
+        #[test]
+        fn should_trim_empty_trailing_fields() {
+            let format = StatusFormat::parse(DEFAULT_TEMPLATE).unwrap();
+            let status = Status {
+                mode: Mode::Rest,
+                long_rest: true,
+                paused: true,
+                task: None,
+                ..status()
+            };
+
+            assert_eq!(format.render(&status), "Long rest 15:00");
+        }
+
+        #[test]
+        fn should_color_the_bar_formats() {
+            assert_eq!(
+                StatusFormat::Tmux.render(&status()),
+                "#[fg=red]Focus 15:00 · Write report#[default]"
+            );
+            let paused = Status {
+                paused: true,
+                task: None,
+                ..status()
+            };
+            assert_eq!(
+                StatusFormat::Polybar.render(&paused),
+                "%{F#e5c07b}Focus 15:00 (paused)%{F-}"
+            );
+        }
+
+        #[test]
+        fn should_escape_task_names_in_the_bar_formats() {
+            let status = Status {
+                task: Some(Task::new("Fix #[bold] 50%{F-}", "")),
+                ..status()
+            };
+
+            assert_eq!(
+                StatusFormat::Tmux.render(&status),
+                "#[fg=red]Focus 15:00 · Fix ##[bold] 50%{F-}#[default]"
+            );
+            assert_eq!(
+                StatusFormat::Polybar.render(&status),
+                "%{F#e06c75}Focus 15:00 · Fix #[bold] 50%%{F-}%{F-}"
+            );
+        }
+
+        #[test]
+        fn should_write_waybar_json() {
+            let line = StatusFormat::Waybar.render(&status());
+            let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+
+            assert_eq!(value["text"], "Focus 15:00 · Write report");
+            assert_eq!(value["class"], "focus");
+            assert_eq!(value["percentage"], 40);
+            assert_eq!(
+                value["tooltip"],
+                "Focus 15:00 left of 25:00\nGoal: 3/8\nTask: Write report"
+            );
+            assert!(!line.contains('\n'));
+        }
+    }
+}
@@ -0,0 +1,584 @@
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::backend::{Pomodoro, Task, TaskFilter, TimerType};
+use crate::config::{format_duration, parse_duration, Config, ConfigError, Resume};
+use crate::daemon::{Client, Event, Request};
+use crate::frontend::tui_ratatui::TuiRatatuiDisplay;
+
+mod format;
+pub use format::*;
+
+/// Config or data files that couldn't be read or written, or no daemon to
+/// attach to.
+pub const EXIT_ERROR: u8 = 1;
+/// Bad arguments, as reported by clap.
+pub const EXIT_USAGE: u8 = 2;
+/// No task with the given id.
+pub const EXIT_NOT_FOUND: u8 = 3;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "tomatoes",
+    about = "Pomodoro timer and task list",
+    after_help = "Exit status: 0 on success, 1 when the config or data files fail, \
+                  2 on bad arguments and 3 when a task isn't found."
+)]
+pub struct Cli {
+    /// Config file to use instead of $XDG_CONFIG_HOME/tomatoes/config.toml.
+    #[arg(short, long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Opens the terminal UI (the default).
+    Tui(TuiArgs),
+    /// Runs a focus block and the rest after it in the foreground.
+    Start(StartArgs),
+    /// Manages the task list.
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// Shows today's progress and the task counts, or the timer of the
+    /// running `tomatoesd` with `--format` or `--follow`.
+    Status(StatusArgs),
+    /// Shows the interruption, project, tag and estimate report.
+    Report,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct TuiArgs {
+    /// Lets a running `tomatoesd` run the timer.
+    #[arg(long, conflicts_with_all = ["resume", "fresh"])]
+    pub attach: bool,
+    /// Picks up the timer left by the last run without asking.
+    #[arg(long, conflicts_with = "fresh")]
+    pub resume: bool,
+    /// Starts a new focus block, leaving the last run's timer.
+    #[arg(long)]
+    pub fresh: bool,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct StatusArgs {
+    /// `tmux`, `polybar`, `waybar`, or a template with {mode}, {state},
+    /// {remaining}, {duration}, {percentage}, {task}, {preset} and {goal}.
+    #[arg(long, value_parser = StatusFormat::parse)]
+    pub format: Option<StatusFormat>,
+    /// Writes a line every second until the daemon stops.
+    #[arg(long)]
+    pub follow: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StartArgs {
+    /// Focus length, like 25m, 1h30m or 90s.
+    #[arg(long, value_parser = parse_length)]
+    pub focus: Option<Duration>,
+    /// Rest length, like 5m.
+    #[arg(long, value_parser = parse_length)]
+    pub rest: Option<Duration>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TaskCommand {
+    /// Adds a task, written as in the TUI: `Write report +work @deep ~3 due:fri`.
+    Add {
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+    },
+    /// Lists tasks to do, or done ones with `--done`.
+    #[command(alias = "ls")]
+    List {
+        /// Lists done tasks instead.
+        #[arg(long, conflicts_with = "all")]
+        done: bool,
+        /// Lists both.
+        #[arg(long)]
+        all: bool,
+        /// Only tasks matching `+project`, `@tag` or `today`.
+        filter: Option<String>,
+    },
+    /// Marks a task as done.
+    Done { id: u64 },
+    /// Removes a task.
+    #[command(alias = "remove")]
+    Rm { id: u64 },
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Config(ConfigError),
+    Io(io::Error),
+    Daemon { socket: PathBuf, error: io::Error },
+    TaskNotFound(u64),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Config(_) | CliError::Io(_) | CliError::Daemon { .. } => EXIT_ERROR,
+            CliError::TaskNotFound(_) => EXIT_NOT_FOUND,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Config(e) => write!(f, "invalid config: {e}"),
+            CliError::Io(e) => write!(f, "{e}"),
+            CliError::Daemon { socket, error } => {
+                write!(
+                    f,
+                    "could not reach tomatoesd at {}: {error}",
+                    socket.display()
+                )
+            }
+            CliError::TaskNotFound(id) => write!(f, "no task #{id}"),
+        }
+    }
+}
+
+impl From<ConfigError> for CliError {
+    fn from(e: ConfigError) -> Self {
+        CliError::Config(e)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+/// Runs the command line, printing errors to stderr.
+/// A `--focus` or `--rest` length, which like the config ones has to be at
+/// least one second.
+fn parse_length(s: &str) -> Result<Duration, String> {
+    let duration = parse_duration(s)?;
+    if duration < Duration::from_secs(1) {
+        return Err("has to be at least one second".to_string());
+    }
+    Ok(duration)
+}
+
+pub fn run(cli: Cli) -> ExitCode {
+    match execute(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into something like `head` that stopped reading.
+        Err(CliError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("tomatoes: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn execute(cli: Cli) -> Result<(), CliError> {
+    let mut config = Config::load(cli.config.as_deref())?;
+    let command = cli
+        .command
+        .unwrap_or_else(|| Command::Tui(TuiArgs::default()));
+    if let Command::Status(args) = &command {
+        if args.format.is_some() || args.follow {
+            let socket = config.socket_path();
+            let mut out = io::stdout().lock();
+            return status_follow(&socket, args, &mut out).map_err(|error| match error {
+                // Output piped into something that stopped reading.
+                error if error.kind() == io::ErrorKind::BrokenPipe => CliError::Io(error),
+                error => CliError::Daemon { socket, error },
+            });
+        }
+    }
+    if let Command::Task(command) = &command {
+        // A running daemon keeps the tasks in memory and would write over
+        // changes made to the files behind its back.
+        let socket = config.socket_path();
+        let client = match command {
+            TaskCommand::List { .. } => None,
+            _ => Client::connect(&socket).ok(),
+        };
+        if let Some(mut client) = client {
+            let mut out = io::stdout().lock();
+            return task_remote(&mut client, command, &mut out).map_err(|error| match error {
+                CliError::Io(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+                    CliError::Daemon { socket, error }
+                }
+                error => error,
+            });
+        }
+    }
+    if let Command::Tui(args) = &command {
+        if args.resume {
+            config.timer.resume = Resume::Always;
+        } else if args.fresh {
+            config.timer.resume = Resume::Never;
+        }
+    }
+    if let Command::Start(args) = &command {
+        config.timer.focus = args.focus.unwrap_or(config.timer.focus);
+        config.timer.short_break = args.rest.unwrap_or(config.timer.short_break);
+        config.timer.long_break_every = 0;
+    }
+
+    let mut pomodoro = config.pomodoro();
+    pomodoro.load()?;
+
+    let mut out = io::stdout().lock();
+    match command {
+        Command::Tui(args) => {
+            let mut tui = TuiRatatuiDisplay::new(pomodoro, &config)?;
+            if args.attach {
+                let socket = config.socket_path();
+                tui.attach(&socket)
+                    .map_err(|error| CliError::Daemon { socket, error })?;
+            }
+            tui.pomo_loop()?;
+        }
+        Command::Start(_) => {
+            let started = start(&mut pomodoro, &mut out);
+            pomodoro.hooks_wait();
+            started?;
+        }
+        Command::Task(command) => {
+            let done = task(&mut pomodoro, command, &mut out);
+            pomodoro.hooks_wait();
+            done?;
+            pomodoro.save()?;
+        }
+        Command::Status(_) => status(&pomodoro, &mut out)?,
+        Command::Report => write!(out, "{}", pomodoro.report())?,
+    }
+
+    Ok(())
+}
+
+/// Counts down a focus block and a rest, saving each once it is over.
+fn start(pomodoro: &mut Pomodoro, out: &mut impl Write) -> io::Result<()> {
+    let terminal = io::stdout().is_terminal();
+    let first_session = pomodoro.history_get().sessions.len();
+    let mut mode = None;
+
+    while pomodoro.history_get().sessions.len() < first_session + 2 {
+        let current_mode = pomodoro.get_mode();
+        if let TimerType::Transitioning(_) = current_mode {
+            pomodoro.forward();
+            if let Some(e) = pomodoro.alarm_error_take() {
+                eprintln!("tomatoes: could not play the alarm: {e}");
+            }
+            continue;
+        }
+
+        if mode.as_ref() != Some(&current_mode) {
+            if mode.is_some() {
+                pomodoro.save()?;
+                if terminal {
+                    writeln!(out)?;
+                }
+            }
+            if !terminal {
+                writeln!(out, "{current_mode} {}", pomodoro.get_current_timer())?;
+            }
+            mode = Some(current_mode.clone());
+        }
+        if terminal {
+            write!(out, "\r{current_mode} {} ", pomodoro.get_current_timer())?;
+            out.flush()?;
+        }
+
+        std::thread::sleep(Duration::from_secs(1));
+        pomodoro.forward();
+    }
+
+    pomodoro.save()?;
+    if terminal {
+        writeln!(out)?;
+    }
+    let preset = pomodoro.preset_get();
+    writeln!(
+        out,
+        "Done: {} focus, {} rest",
+        format_duration(preset.focus),
+        format_duration(preset.rest)
+    )
+}
+
+fn task(
+    pomodoro: &mut Pomodoro,
+    command: TaskCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        TaskCommand::Add { text } => {
+            let task = Task::from_str(&text.join(" "));
+            writeln!(out, "Added #{} {}", task.id, task.name)?;
+            pomodoro.task_add(task);
+        }
+        TaskCommand::List { done, all, filter } => {
+            pomodoro.filter_set(filter.as_deref().and_then(TaskFilter::parse));
+            let lists = match (done, all) {
+                (_, true) => vec![false, true],
+                (true, _) => vec![true],
+                _ => vec![false],
+            };
+            for completed in lists {
+                for task in pomodoro.task_get_by_complete(completed) {
+                    writeln!(out, "{}", task_line(&task))?;
+                }
+            }
+        }
+        TaskCommand::Done { id } => {
+            let task = pomodoro.task_get(id).ok_or(CliError::TaskNotFound(id))?;
+            match pomodoro.task_position(id) {
+                Some((false, index)) => {
+                    pomodoro.task_complete(index);
+                    writeln!(out, "Done #{id} {}", task.name)?;
+                }
+                _ => writeln!(out, "#{id} {} was already done", task.name)?,
+            }
+        }
+        TaskCommand::Rm { id } => {
+            let task = pomodoro.task_get(id).ok_or(CliError::TaskNotFound(id))?;
+            pomodoro.task_remove_by_attributes(task.clone());
+            writeln!(out, "Removed #{id} {}", task.name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a task command on the daemon, answering like [`task`] does.
+fn task_remote(
+    client: &mut Client,
+    command: &TaskCommand,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    let (request, id) = match command {
+        TaskCommand::Add { text } => (
+            Request::TaskAdd {
+                text: text.join(" "),
+            },
+            None,
+        ),
+        TaskCommand::Done { id } => (Request::TaskDone { id: *id }, Some(*id)),
+        TaskCommand::Rm { id } => (Request::TaskRemove { id: *id }, Some(*id)),
+        TaskCommand::List { .. } => unreachable!("the task list is read from the files"),
+    };
+    let was_done = match command {
+        TaskCommand::Done { id } => client
+            .request(&Request::TaskList { done: true })?
+            .tasks
+            .is_some_and(|tasks| tasks.iter().any(|task| task.id == *id)),
+        _ => false,
+    };
+
+    let response = client.request(&request)?;
+    let task = match (response.ok, response.task, id) {
+        (true, Some(task), _) => task,
+        (false, _, Some(id)) if response.error.as_deref() == Some(&format!("no task #{id}")) => {
+            return Err(CliError::TaskNotFound(id));
+        }
+        (_, _, _) => {
+            let error = response
+                .error
+                .unwrap_or_else(|| "no task in the answer".into());
+            return Err(CliError::Io(io::Error::other(error)));
+        }
+    };
+    match command {
+        TaskCommand::Add { .. } => writeln!(out, "Added #{} {}", task.id, task.name)?,
+        TaskCommand::Done { .. } if was_done => {
+            writeln!(out, "#{} {} was already done", task.id, task.name)?
+        }
+        TaskCommand::Done { .. } => writeln!(out, "Done #{} {}", task.id, task.name)?,
+        _ => writeln!(out, "Removed #{} {}", task.id, task.name)?,
+    }
+
+    Ok(())
+}
+
+fn task_line(task: &Task) -> String {
+    let check = if task.completed { "x" } else { " " };
+    format!("#{} [{check}] {}", task.id, task.to_input_string())
+}
+
+fn status(pomodoro: &Pomodoro, out: &mut impl Write) -> io::Result<()> {
+    let streaks = pomodoro.streaks_get();
+    writeln!(
+        out,
+        "Goal: {} | Streak: {} (best: {})",
+        pomodoro.goal_progress(),
+        streaks.current,
+        streaks.longest
+    )?;
+    writeln!(
+        out,
+        "Tasks: {} to do, {} done",
+        pomodoro.task_get_by_complete(false).len(),
+        pomodoro.task_get_by_complete(true).len()
+    )
+}
+
+/// Writes the daemon's timer once, or on every tick with `--follow`.
+fn status_follow(socket: &Path, args: &StatusArgs, out: &mut impl Write) -> io::Result<()> {
+    let format = match &args.format {
+        Some(format) => format.clone(),
+        None => StatusFormat::parse(DEFAULT_TEMPLATE).map_err(io::Error::other)?,
+    };
+
+    let mut client = Client::connect(socket)?;
+    if !args.follow {
+        let status = client
+            .request(&Request::Status)?
+            .status
+            .ok_or_else(|| io::Error::other("the daemon sent no status"))?;
+        return writeln!(out, "{}", format.render(&status));
+    }
+
+    let (status, events) = client.subscribe()?;
+    writeln!(out, "{}", format.render(&status))?;
+    out.flush()?;
+    for event in events {
+        if let Event::Tick { status } | Event::Phase { status } = event {
+            writeln!(out, "{}", format.render(&status))?;
+            out.flush()?;
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "the daemon stopped",
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("tomatoes").chain(args.iter().copied()))
+    }
+
+    mod cli {
+        use super::*;
+
+        #[test]
+        fn should_be_well_formed() {
+            Cli::command().debug_assert();
+        }
+
+        #[test]
+        fn should_parse_human_durations() {
+            let cli = parse(&["start", "--focus", "1h30m", "--rest", "90s"]).unwrap();
+
+            match cli.command {
+                Some(Command::Start(args)) => {
+                    assert_eq!(args.focus, Some(Duration::from_secs(90 * 60)));
+                    assert_eq!(args.rest, Some(Duration::from_secs(90)));
+                }
+                command => panic!("unexpected {command:?}"),
+            }
+            assert!(parse(&["start", "--focus", "soon"]).is_err());
+        }
+
+        #[test]
+        fn should_reject_lengths_under_a_second() {
+            let error = parse(&["start", "--focus", "0s", "--rest", "1s"]).unwrap_err();
+
+            assert_eq!(error.exit_code(), EXIT_USAGE as i32);
+            assert!(parse(&["start", "--rest", "0"]).is_err());
+        }
+
+        #[test]
+        fn should_parse_task_commands() {
+            let cli = parse(&["task", "add", "Write", "report", "+work"]).unwrap();
+            assert!(matches!(
+                cli.command,
+                Some(Command::Task(TaskCommand::Add { text })) if text.join(" ") == "Write report +work"
+            ));
+
+            let cli = parse(&["--config", "/tmp/c.toml", "task", "done", "4"]).unwrap();
+            assert_eq!(cli.config, Some(PathBuf::from("/tmp/c.toml")));
+            assert!(matches!(
+                cli.command,
+                Some(Command::Task(TaskCommand::Done { id: 4 }))
+            ));
+
+            let error = parse(&["task", "rm", "four"]).unwrap_err();
+            assert_eq!(error.exit_code(), EXIT_USAGE as i32);
+        }
+
+        #[test]
+        fn should_parse_status_formats() {
+            let cli = parse(&["status", "--format", "waybar", "--follow"]).unwrap();
+            assert!(matches!(
+                cli.command,
+                Some(Command::Status(StatusArgs {
+                    format: Some(StatusFormat::Waybar),
+                    follow: true
+                }))
+            ));
+
+            let error = parse(&["status", "--format", "{left}"]).unwrap_err();
+            assert_eq!(error.exit_code(), EXIT_USAGE as i32);
+        }
+
+        #[test]
+        fn should_default_to_the_tui() {
+            assert!(parse(&[]).unwrap().command.is_none());
+            assert!(matches!(
+                parse(&["tui", "--attach"]).unwrap().command,
+                Some(Command::Tui(TuiArgs { attach: true, .. }))
+            ));
+            assert!(parse(&["tui", "--resume", "--fresh"]).is_err());
+        }
+    }
+
+    mod task {
+        use super::*;
+
+        #[test]
+        fn should_report_missing_tasks() {
+            let mut pomodoro = Pomodoro::new(Duration::from_secs(15), Duration::from_secs(15));
+
+            let error = task(
+                &mut pomodoro,
+                TaskCommand::Done { id: u64::MAX },
+                &mut vec![],
+            )
+            .unwrap_err();
+
+            assert_eq!(error.exit_code(), EXIT_NOT_FOUND);
+            assert_eq!(error.to_string(), format!("no task #{}", u64::MAX));
+        }
+
+        #[test]
+        fn should_complete_and_remove_by_id() {
+            let mut pomodoro = Pomodoro::new(Duration::from_secs(15), Duration::from_secs(15));
+            let first = Task::new("First", "");
+            let second = Task::new("Second", "");
+            pomodoro.task_add(first.clone());
+            pomodoro.task_add(second.clone());
+
+            let mut out = vec![];
+            task(&mut pomodoro, TaskCommand::Done { id: second.id }, &mut out).unwrap();
+            assert_eq!(pomodoro.task_position(second.id), Some((true, 0)));
+
+            task(&mut pomodoro, TaskCommand::Rm { id: first.id }, &mut out).unwrap();
+            assert_eq!(pomodoro.task_get(first.id), None);
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                format!("Done #{} Second\nRemoved #{} First\n", second.id, first.id)
+            );
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod cli;
 pub mod terminal;
 pub mod tui_ratatui;
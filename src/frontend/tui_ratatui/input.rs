@@ -0,0 +1,437 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Text buffer with a cursor and a history of submitted entries. Single line
+/// unless made with `multiline`, where `Enter` breaks lines and `Up`/`Down`
+/// move between them instead of going through the history.
+///
+/// The cursor is a byte index that always sits on a grapheme boundary, so
+/// moving and deleting handle accents and emoji as a single character.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    draft: String,
+    multiline: bool,
+}
+
+impl TextInput {
+    pub fn multiline() -> Self {
+        Self {
+            multiline: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, keeping the history, with the cursor at the end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+        self.history_index = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set(String::new());
+    }
+
+    pub fn take(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.clear();
+        text
+    }
+
+    /// Takes the text and remembers it for `history_prev`.
+    pub fn submit(&mut self) -> String {
+        let text = self.take();
+        if !text.trim().is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+        text
+    }
+
+    /// Display width of the text before the cursor.
+    pub fn cursor_width(&self) -> u16 {
+        self.text[..self.cursor].width() as u16
+    }
+
+    /// Column, as display width, and line of the cursor.
+    pub fn cursor_position(&self) -> (u16, u16) {
+        let before = &self.text[..self.cursor];
+        let line_start = self.line_start(self.cursor);
+        let row = before.matches('\n').count();
+        (before[line_start..].width() as u16, row as u16)
+    }
+
+    fn line_start(&self, index: usize) -> usize {
+        self.text[..index].rfind('\n').map(|i| i + 1).unwrap_or(0)
+    }
+
+    fn line_end(&self, index: usize) -> usize {
+        self.text[index..]
+            .find('\n')
+            .map(|i| index + i)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Byte index in the line starting at `line_start` closest to `column`.
+    fn line_index_at(&self, line_start: usize, column: usize) -> usize {
+        let line = &self.text[line_start..self.line_end(line_start)];
+        let mut width = 0;
+        for (i, grapheme) in line.grapheme_indices(true) {
+            if width >= column {
+                return line_start + i;
+            }
+            width += grapheme.width();
+        }
+        line_start + line.len()
+    }
+
+    /// Moves to the line above, keeping the column when it fits.
+    pub fn up(&mut self) {
+        let line_start = self.line_start(self.cursor);
+        if line_start == 0 {
+            return;
+        }
+
+        let column = self.text[line_start..self.cursor].width();
+        self.cursor = self.line_index_at(self.line_start(line_start - 1), column);
+    }
+
+    /// Moves to the line below, keeping the column when it fits.
+    pub fn down(&mut self) {
+        let line_end = self.line_end(self.cursor);
+        if line_end == self.text.len() {
+            return;
+        }
+
+        let column = self.text[self.line_start(self.cursor)..self.cursor].width();
+        self.cursor = self.line_index_at(line_end + 1, column);
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Inserts pasted text, folding line breaks into spaces unless the input
+    /// is multiline.
+    pub fn insert_str(&mut self, s: &str) {
+        let s: String = if self.multiline {
+            s.replace("\r\n", "\n").replace('\r', "\n")
+        } else {
+            s.trim_end_matches(['\n', '\r'])
+                .chars()
+                .map(|c| if c == '\n' || c == '\r' { ' ' } else { c })
+                .collect()
+        };
+        self.text.insert_str(self.cursor, &s);
+        self.cursor += s.len();
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map(|g| self.cursor + g.len())
+            .unwrap_or(self.text.len())
+    }
+
+    pub fn backspace(&mut self) {
+        let start = self.prev_boundary();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn delete(&mut self) {
+        let end = self.next_boundary();
+        self.text.replace_range(self.cursor..end, "");
+    }
+
+    /// Deletes back to the start of the previous word, like `Ctrl-w` in a shell.
+    pub fn delete_word(&mut self) {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end();
+        let start = trimmed
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(0);
+
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Recalls the previous submitted entry, saving what was being typed.
+    pub fn history_prev(&mut self) {
+        let index = match self.history_index {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.len() - 1
+            }
+        };
+
+        self.text = self.history[index].clone();
+        self.cursor = self.text.len();
+        self.history_index = Some(index);
+    }
+
+    pub fn history_next(&mut self) {
+        let index = match self.history_index {
+            Some(index) => index + 1,
+            None => return,
+        };
+
+        if index < self.history.len() {
+            self.text = self.history[index].clone();
+            self.history_index = Some(index);
+        } else {
+            self.text = std::mem::take(&mut self.draft);
+            self.history_index = None;
+        }
+        self.cursor = self.text.len();
+    }
+
+    /// Applies an editing key. Returns `false` for keys the input doesn't
+    /// handle, like `Esc` or, on a single line, `Enter`.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char('a') if ctrl => self.home(),
+            KeyCode::Char('e') if ctrl => self.end(),
+            KeyCode::Backspace if ctrl || alt => self.delete_word(),
+            KeyCode::Char(_) if ctrl => return false,
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Enter if self.multiline => self.insert('\n'),
+            KeyCode::Up if self.multiline => self.up(),
+            KeyCode::Down if self.multiline => self.down(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            KeyCode::Up => self.history_prev(),
+            KeyCode::Down => self.history_next(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input_with(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        input.set(text);
+        input
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn should_insert_at_the_cursor() {
+            let mut input = input_with("Tsk");
+            input.left();
+            input.left();
+            input.insert('a');
+
+            assert_eq!(input.text(), "Task");
+        }
+    }
+
+    mod insert_str {
+        use super::*;
+
+        #[test]
+        fn should_fold_pasted_line_breaks() {
+            let mut input = input_with("Task: ");
+            input.insert_str("first line\nsecond line\n");
+
+            assert_eq!(input.text(), "Task: first line second line");
+        }
+    }
+
+    mod backspace {
+        use super::*;
+
+        #[test]
+        fn should_remove_the_char_before_the_cursor() {
+            let mut input = input_with("Taskk");
+            input.backspace();
+            assert_eq!(input.text(), "Task");
+
+            input.home();
+            input.backspace();
+            assert_eq!(input.text(), "Task");
+        }
+
+        #[test]
+        fn should_remove_whole_graphemes() {
+            let mut input = input_with("Tarefa: ac\u{0327}a\u{0303}o 👩‍💻");
+            input.backspace();
+            assert_eq!(input.text(), "Tarefa: ac\u{0327}a\u{0303}o ");
+
+            input.left();
+            input.left();
+            input.backspace();
+            assert_eq!(input.text(), "Tarefa: ac\u{0327}o ");
+        }
+    }
+
+    mod delete_word {
+        use super::*;
+
+        #[test]
+        fn should_delete_the_previous_word() {
+            let mut input = input_with("Read chapter two  ");
+            input.delete_word();
+            assert_eq!(input.text(), "Read chapter ");
+
+            input.left();
+            input.left();
+            input.delete_word();
+            assert_eq!(input.text(), "Read r ");
+        }
+    }
+
+    mod cursor_width {
+        use super::*;
+
+        #[test]
+        fn should_count_display_columns() {
+            let mut input = input_with("番茄 ok");
+            assert_eq!(input.cursor_width(), 7);
+
+            input.home();
+            input.right();
+            assert_eq!(input.cursor_width(), 2);
+        }
+    }
+
+    mod history {
+        use super::*;
+
+        #[test]
+        fn should_recall_submitted_entries() {
+            let mut input = TextInput::default();
+            input.set("First");
+            input.submit();
+            input.set("Second");
+            input.submit();
+            input.set("Draft");
+
+            input.history_prev();
+            assert_eq!(input.text(), "Second");
+            input.history_prev();
+            assert_eq!(input.text(), "First");
+            input.history_prev();
+            assert_eq!(input.text(), "First");
+
+            input.history_next();
+            assert_eq!(input.text(), "Second");
+            input.history_next();
+            assert_eq!(input.text(), "Draft");
+        }
+
+        #[test]
+        fn should_skip_empty_and_repeated_entries() {
+            let mut input = TextInput::default();
+            input.set("Task");
+            input.submit();
+            input.set("Task");
+            input.submit();
+            input.set("   ");
+            input.submit();
+
+            assert_eq!(input.history, vec!["Task".to_string()]);
+        }
+    }
+
+    mod up {
+        use super::*;
+
+        #[test]
+        fn should_keep_the_column_across_lines() {
+            let mut input = TextInput::multiline();
+            input.insert_str("First line\r\nab\nThird line");
+            assert_eq!(input.cursor_position(), (10, 2));
+
+            input.up();
+            assert_eq!(input.cursor_position(), (2, 1));
+            input.up();
+            assert_eq!(input.cursor_position(), (2, 0));
+            input.up();
+            assert_eq!(input.cursor_position(), (2, 0));
+
+            input.down();
+            input.down();
+            input.insert('X');
+            assert_eq!(input.text(), "First line\nab\nThXird line");
+        }
+    }
+
+    mod handle_key {
+        use super::*;
+
+        #[test]
+        fn should_break_lines_when_multiline() {
+            let mut input = TextInput::multiline();
+            input.set("Notes");
+
+            assert!(input.handle_key(KeyEvent::from(KeyCode::Enter)));
+            assert_eq!(input.text(), "Notes\n");
+            assert!(!input.handle_key(KeyEvent::from(KeyCode::Esc)));
+        }
+
+        #[test]
+        fn should_not_handle_submit_keys() {
+            let mut input = TextInput::default();
+
+            assert!(!input.handle_key(KeyEvent::from(KeyCode::Enter)));
+            assert!(!input.handle_key(KeyEvent::from(KeyCode::Esc)));
+            assert!(input.handle_key(KeyEvent::from(KeyCode::Char(' '))));
+            assert_eq!(input.text(), " ");
+        }
+    }
+}
@@ -1,24 +1,51 @@
 use std::{
-    io::{self, stdout, Stdout},
-    time::{Duration, SystemTime},
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, stdout, Stdout, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use chrono::{DateTime, Local, NaiveDate};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::{
     backend::CrosstermBackend,
+    layout::Margin,
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders, Padding, Paragraph},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
     Terminal,
 };
 
-use crate::backend::{Pomodoro, Task, TimerType};
+use crate::config::{format_duration, Config, Key, PaletteAction, Resume};
+use crate::daemon::{self, Request, Status};
+
+use crate::backend::{
+    format_date, format_hours, InterruptionKind, PhaseChange, Pomodoro, Priority, SleepWatch,
+    Snapshot, Subtask, SuspendHandling, Task, TaskFilter, TimerType, SNAPSHOT_INTERVAL,
+};
+
+mod input;
+mod palette;
+mod remote;
+use input::TextInput;
+use palette::{palette_items, PaletteItem};
+use remote::Remote;
 
 const COL_SIZE: usize = 3;
+const STATUS_TIME: Duration = Duration::from_secs(3);
+const ATTACHED_ONLY: &str = "Not available while attached to tomatoesd";
+/// How long "still working?" waits for an answer before pausing.
+const IDLE_ANSWER_TIME: Duration = Duration::from_secs(60);
 
 #[derive(Debug, PartialEq, Eq)]
 enum Area {
@@ -26,6 +53,42 @@ enum Area {
     TasksNotCompleted,
     TasksCompleted,
     TaskAdd,
+    Report,
+    InterruptionNote(InterruptionKind),
+    TaskEdit(u64),
+    Filter,
+    SubtaskAdd(u64),
+    TaskDetail(u64),
+    NotesEdit(u64),
+    Archive,
+    Palette,
+    Presets,
+    /// Asking what to do with the running phase before switching to the
+    /// preset at this index.
+    PresetChange(usize),
+    /// Asking whether to pick up the timer left by the last run.
+    Resume,
+}
+
+impl Area {
+    fn is_text_input(&self) -> bool {
+        matches!(
+            self,
+            Area::TaskAdd
+                | Area::InterruptionNote(_)
+                | Area::TaskEdit(_)
+                | Area::Filter
+                | Area::SubtaskAdd(_)
+        )
+    }
+}
+
+/// A line of a task list: a task, or one of its subtasks when the task is
+/// expanded. Indexes point into `Pomodoro::task_get_by_complete`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TaskRow {
+    task: usize,
+    subtask: Option<usize>,
 }
 
 pub struct TuiRatatuiDisplay {
@@ -37,15 +100,40 @@ pub struct TuiRatatuiDisplay {
     selected_col: usize,
     pause: bool,
     space_timeout: SystemTime,
-    new_task_buffer: String,
+    input: TextInput,
+    /// The filter prompt, apart from `input` so filters stay out of the task
+    /// history.
+    filter: TextInput,
+    notes: TextInput,
+    detail_scroll: u16,
+    archive_row: usize,
+    palette_row: usize,
+    preset_row: usize,
+    collapsed: HashSet<u64>,
+    status: Option<(String, SystemTime)>,
     autopause: bool,
-    // TODO: Add a autopause mode option.
+    space_delay: Duration,
+    keys: HashMap<PaletteAction, Key>,
+    remote: Option<Remote>,
+    /// The timer left by the last run, while asking whether to resume it.
+    resume: Option<Snapshot>,
+    snapshot_at: SystemTime,
+    snapshot_state: (TimerType, bool),
+    on_suspend: SuspendHandling,
+    sleep_watch: SleepWatch,
+    /// What happened while the computer slept, shown until the next key.
+    notice: Option<String>,
+    idle_after: Option<Duration>,
+    /// The last key, or when focus last started running.
+    activity_at: DateTime<Local>,
+    /// When we asked whether anyone is still working, until they answer.
+    idle_asked: Option<DateTime<Local>>,
 }
 
 impl TuiRatatuiDisplay {
-    pub fn new(pomodoro: Pomodoro) -> Result<Self, io::Error> {
+    pub fn new(pomodoro: Pomodoro, config: &Config) -> Result<Self, io::Error> {
         let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-        Ok(Self {
+        let mut display = Self {
             pomodoro,
             terminal,
             should_close: false,
@@ -54,35 +142,300 @@ impl TuiRatatuiDisplay {
             selected_row: 0,
             selected_col: 0,
             space_timeout: SystemTime::now(),
-            new_task_buffer: String::new(),
-            autopause: true,
-        })
+            input: TextInput::default(),
+            filter: TextInput::default(),
+            notes: TextInput::multiline(),
+            detail_scroll: 0,
+            archive_row: 0,
+            palette_row: 0,
+            preset_row: 0,
+            collapsed: HashSet::new(),
+            status: None,
+            autopause: config.timer.autopause,
+            space_delay: config.ui.space_debounce,
+            keys: config.keys.clone(),
+            remote: None,
+            resume: None,
+            snapshot_at: SystemTime::now(),
+            snapshot_state: (TimerType::Focus, false),
+            on_suspend: config.timer.on_suspend,
+            sleep_watch: SleepWatch::default(),
+            notice: None,
+            idle_after: config.ui.idle_after,
+            activity_at: Local::now(),
+            idle_asked: None,
+        };
+        display.resume_start(config.timer.resume);
+        Ok(display)
+    }
+
+    /// Offers to pick up the timer left by the last run, or just does it.
+    fn resume_start(&mut self, resume: Resume) {
+        let snapshot = match self.pomodoro.snapshot_load() {
+            Ok(Some(snapshot)) if !snapshot.is_fresh() => snapshot,
+            _ => return,
+        };
+        match resume {
+            Resume::Never => {}
+            Resume::Always => self.resume_apply(snapshot),
+            Resume::Ask => {
+                self.resume = Some(snapshot);
+                self.current_area = Area::Resume;
+            }
+        }
+    }
+
+    fn resume_apply(&mut self, snapshot: Snapshot) {
+        self.pause = self.pomodoro.resume(&snapshot, Local::now());
+        let message = format!(
+            "Resumed: {} with {} left",
+            self.pomodoro.get_mode(),
+            self.pomodoro.get_current_timer()
+        );
+        self.status_set(message);
+        self.snapshot_save();
+    }
+
+    fn resume_handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                if let Some(snapshot) = self.resume.take() {
+                    self.resume_apply(snapshot);
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.resume = None,
+            _ => return,
+        }
+        self.current_area = Area::Timer;
+    }
+
+    /// Deals with the computer having slept from `from` to `to` while the
+    /// timer ran.
+    fn suspended(&mut self, from: DateTime<Local>, to: DateTime<Local>) {
+        self.pause = self.pomodoro.suspend(from, to, self.on_suspend);
+        self.notice = self
+            .pomodoro
+            .history_get()
+            .suspends
+            .last()
+            .map(ToString::to_string);
+        self.snapshot_save();
+    }
+
+    /// Asks whether anyone is still working after `idle_after` without a
+    /// key during focus, and pauses when no one answers. Idle time only
+    /// counts while focus runs.
+    fn idle_check(&mut self) {
+        let Some(idle_after) = self.idle_after else {
+            return;
+        };
+        let now = Local::now();
+        let running = !self.pause && self.resume.is_none();
+        if !running || self.pomodoro.get_mode() != TimerType::Focus {
+            self.activity_at = now;
+            self.idle_asked = None;
+            return;
+        }
+
+        let since = |at: DateTime<Local>| (now - at).to_std().unwrap_or(Duration::ZERO);
+        match self.idle_asked {
+            None if since(self.activity_at) >= idle_after => self.idle_asked = Some(now),
+            Some(asked) if since(asked) >= IDLE_ANSWER_TIME => self.idle_pause(),
+            _ => {}
+        }
+    }
+
+    /// Pauses as of the last key, giving the time since back to the focus
+    /// block.
+    fn idle_pause(&mut self) {
+        self.idle_asked = None;
+        let from = self.activity_at;
+        if self.pomodoro.idle_pause(from, Local::now()) {
+            self.pause = true;
+            self.notice = Some(format!(
+                "Paused: idle since {}, {} left",
+                from.format("%H:%M"),
+                self.pomodoro.get_current_timer()
+            ));
+            self.snapshot_save();
+        }
+    }
+
+    fn idle_handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.idle_asked = None;
+                self.activity_at = Local::now();
+            }
+            KeyCode::Char('n') | KeyCode::Esc => self.idle_pause(),
+            _ => {}
+        }
+    }
+
+    /// Saves the timer for the next run to resume. Not while attached, as
+    /// the daemon saves its own.
+    fn snapshot_save(&mut self) {
+        if self.remote.is_some() {
+            return;
+        }
+        let _ = self.pomodoro.snapshot_save(self.pause);
+        self.snapshot_at = SystemTime::now();
+        self.snapshot_state = (self.pomodoro.get_mode(), self.pause);
+    }
+
+    /// Lets the daemon listening on `socket` run the timer from now on.
+    pub fn attach(&mut self, socket: &Path) -> io::Result<()> {
+        let (remote, status) = Remote::connect(socket, self.pomodoro.revision())?;
+        self.remote = Some(remote);
+        if self.resume.take().is_some() {
+            self.current_area = Area::Timer;
+        }
+        self.remote_status_apply(status);
+        Ok(())
+    }
+
+    fn remote_status_apply(&mut self, status: Status) {
+        self.pomodoro
+            .timer_sync(status.mode.into(), status.timer(), &status.preset);
+        self.pause = status.paused;
+    }
+
+    /// Sends a timer request to the daemon and shows what came of it.
+    fn remote_request(&mut self, request: Request) {
+        let Some(remote) = self.remote.as_mut() else {
+            return;
+        };
+        match remote.request(&request) {
+            Ok(response) => {
+                if let Some(status) = response.status {
+                    self.remote_status_apply(status);
+                }
+            }
+            Err(e) => self.status_set(format!("tomatoesd: {e}")),
+        }
+    }
+
+    /// Hands local task changes to the daemon and takes in its events.
+    /// Falls back to a local, paused, timer when the daemon goes away.
+    fn remote_sync(&mut self) {
+        let Some(remote) = self.remote.as_mut() else {
+            return;
+        };
+
+        if remote.revision != self.pomodoro.revision() {
+            remote.revision = self.pomodoro.revision();
+            let handed = self
+                .pomodoro
+                .tasks_save()
+                .map_err(|e| e.to_string())
+                .and_then(|()| remote.reload());
+            if let Err(e) = handed {
+                self.status_set(format!("Could not hand the tasks to tomatoesd: {e}"));
+            }
+        }
+
+        let Some(events) = self.remote.as_mut().and_then(Remote::events) else {
+            self.remote = None;
+            self.pause = true;
+            self.status_set("Lost tomatoesd, the timer runs here now".to_string());
+            return;
+        };
+        for event in events {
+            let loaded = match event {
+                daemon::Event::Tick { status } => {
+                    self.remote_status_apply(status);
+                    Ok(())
+                }
+                daemon::Event::Phase { status } => {
+                    self.remote_status_apply(status);
+                    self.pomodoro.history_load()
+                }
+                daemon::Event::Suspended { suspend, status } => {
+                    self.notice = Some(suspend.to_string());
+                    self.remote_status_apply(status);
+                    self.pomodoro.history_load()
+                }
+                daemon::Event::Tasks => self.pomodoro.tasks_load(),
+            };
+            if let Err(e) = loaded {
+                self.status_set(format!("Could not read what tomatoesd saved: {e}"));
+            }
+        }
     }
 
     pub fn display(&mut self) -> io::Result<()> {
         let height = self.terminal.size().ok().unwrap().height;
         // Timer
+        let status = self
+            .status
+            .as_ref()
+            .filter(|(_, at)| at.elapsed().is_ok_and(|elapsed| elapsed < STATUS_TIME))
+            .map(|(message, _)| message.as_str())
+            .or(self.notice.as_deref());
         let timer_widget = Self::create_timer_widget(
             &self.pomodoro,
             &self.current_area,
             height >> 5,
             self.selected_col,
+            status,
         );
 
         // Completed tasks
         let not_completed_widget = Self::create_not_completed_widget(
             &self.pomodoro,
             &self.current_area,
+            &self.collapsed,
             self.selected_row,
         );
 
         // Completed tasks
-        let completed_widget =
-            Self::create_completed_widget(&self.pomodoro, &self.current_area, self.selected_row);
+        let completed_widget = Self::create_completed_widget(
+            &self.pomodoro,
+            &self.current_area,
+            &self.collapsed,
+            self.selected_row,
+        );
 
-        // Task add section
-        let task_add_widget =
-            Self::create_add_task(&self.current_area, self.new_task_buffer.clone());
+        // Task add and interruption note section
+        let input_widget = Self::create_input_widget(&self.current_area, self.input());
+        let input_cursor = self.input().cursor_width();
+
+        // Report popup
+        let report_widget = Self::create_report_widget(&self.pomodoro, &self.current_area);
+
+        // Presets popup
+        let presets_widget =
+            Self::create_presets_widget(&self.pomodoro, &self.current_area, self.preset_row);
+        let resume_widget = Self::create_resume_widget(&self.current_area, self.resume.as_ref());
+        let idle_widget = self
+            .idle_asked
+            .map(|_| Self::create_idle_widget(self.activity_at));
+
+        // Task detail popup and notes editor
+        let detail_widget =
+            Self::create_detail_widget(&self.pomodoro, &self.current_area, self.detail_scroll);
+        let notes_widget = Self::create_notes_widget(&self.current_area, &self.notes);
+        let (notes_col, notes_row) = self.notes.cursor_position();
+
+        // Archive search and command palette popups
+        let search_widgets = Self::create_archive_widgets(
+            &self.pomodoro,
+            &self.current_area,
+            &self.input,
+            self.archive_row,
+        )
+        .or_else(|| {
+            Self::create_palette_widgets(
+                &self.pomodoro,
+                &self.current_area,
+                &self.input,
+                self.palette_row,
+            )
+        });
+        let list_row = match self.current_area {
+            Area::Archive => self.archive_row,
+            _ => self.palette_row,
+        } as u16;
 
         self.terminal.draw(|frame| {
             let frame_area = frame.size();
@@ -99,12 +452,74 @@ impl TuiRatatuiDisplay {
 
             frame.render_widget(completed_widget, done_task_area);
 
-            if let Some(task_add_widget) = task_add_widget {
-                let mut task_add_area = done_task_area.clone();
-                task_add_area.height /= 2;
-                task_add_area.y = done_task_area.y + done_task_area.height;
+            if let Some(input_widget) = input_widget {
+                let mut input_area = done_task_area.clone();
+                input_area.height /= 2;
+                input_area.y = done_task_area.y + done_task_area.height;
+
+                // Scroll sideways to keep the cursor inside the borders.
+                let text_width = input_area.width.saturating_sub(2);
+                let scroll = input_cursor.saturating_sub(text_width.saturating_sub(1));
+                frame.render_widget(input_widget.scroll((0, scroll)), input_area);
+                frame.set_cursor(input_area.x + 1 + input_cursor - scroll, input_area.y + 1);
+            }
+
+            if let Some(report_widget) = report_widget {
+                let report_area = frame_area.inner(&Margin::new(4, 2));
+                frame.render_widget(Clear, report_area);
+                frame.render_widget(report_widget, report_area);
+            }
+
+            let popup_area = frame_area.inner(&Margin::new(4, 2));
+            if let Some(detail_widget) = detail_widget {
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(detail_widget, popup_area);
+            }
+
+            if let Some(presets_widget) = presets_widget {
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(presets_widget, popup_area);
+            }
+
+            if let Some(resume_widget) = resume_widget {
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(resume_widget, popup_area);
+            }
+
+            if let Some((search_widget, list_widget)) = search_widgets {
+                let mut search_area = popup_area;
+                search_area.height = 3;
+                let mut list_area = popup_area;
+                list_area.y += search_area.height;
+                list_area.height = list_area.height.saturating_sub(search_area.height);
+
+                let text_width = search_area.width.saturating_sub(2);
+                let scroll = input_cursor.saturating_sub(text_width.saturating_sub(1));
+                // Scroll the list to keep the selected row inside the borders.
+                let list_height = list_area.height.saturating_sub(2);
+                let list_scroll = list_row.saturating_sub(list_height.saturating_sub(1));
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(search_widget.scroll((0, scroll)), search_area);
+                frame.render_widget(list_widget.scroll((list_scroll, 0)), list_area);
+                frame.set_cursor(search_area.x + 1 + input_cursor - scroll, search_area.y + 1);
+            }
 
-                frame.render_widget(task_add_widget, task_add_area);
+            if let Some(notes_widget) = notes_widget {
+                // Scroll to keep the cursor inside the borders.
+                let text_area = popup_area.inner(&Margin::new(1, 1));
+                let scroll_x = notes_col.saturating_sub(text_area.width.saturating_sub(1));
+                let scroll_y = notes_row.saturating_sub(text_area.height.saturating_sub(1));
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(notes_widget.scroll((scroll_y, scroll_x)), popup_area);
+                frame.set_cursor(
+                    text_area.x + notes_col - scroll_x,
+                    text_area.y + notes_row - scroll_y,
+                );
+            }
+
+            if let Some(idle_widget) = idle_widget {
+                frame.render_widget(Clear, popup_area);
+                frame.render_widget(idle_widget, popup_area);
             }
         })?;
 
@@ -116,13 +531,21 @@ impl TuiRatatuiDisplay {
         current_area: &'a Area,
         height: u16,
         selected_col: usize,
+        status: Option<&'a str>,
     ) -> Paragraph<'a> {
         let mut styles = vec![Style::default().bg(Color::Gray)];
         styles = styles.repeat(COL_SIZE);
         styles[selected_col] = styles[selected_col].fg(Color::Red);
 
         let pomo_string = pomodoro.get_current_timer().to_string();
-        let pomo_display: Vec<Line<'_>> = vec![
+        let streaks = pomodoro.streaks_get();
+        let goal_string = format!(
+            "Goal: {} | Streak: {} (best: {})",
+            pomodoro.goal_progress(),
+            streaks.current,
+            streaks.longest
+        );
+        let mut pomo_display: Vec<Line<'_>> = vec![
             Span::from(pomo_string).into(),
             vec![
                 Span::styled("⏵⏸︎ ", styles[0]),
@@ -130,9 +553,36 @@ impl TuiRatatuiDisplay {
                 Span::styled("🗘 ", styles[2]),
             ]
             .into(),
+            Span::from(goal_string).into(),
         ];
+        if let Some(task) = pomodoro.task_active_get() {
+            pomo_display.push(Span::from(format!("Working on: {}", task.name)).into());
+        }
+        let interruptions = pomodoro.interruptions_current();
+        if interruptions.internal + interruptions.external > 0 {
+            pomo_display.push(
+                Span::from(format!(
+                    "Interruptions: {} {} | {} {}",
+                    InterruptionKind::Internal.symbol(),
+                    interruptions.internal,
+                    InterruptionKind::External.symbol(),
+                    interruptions.external
+                ))
+                .into(),
+            );
+        }
+
+        if let Some(status) = status {
+            pomo_display.push(Span::from(status).italic().into());
+        }
 
-        let pomo_mode = pomodoro.get_mode().to_string();
+        let mut pomo_mode = match pomodoro.get_mode() {
+            TimerType::Rest if pomodoro.rest_is_long() => "Long rest".to_string(),
+            mode => mode.to_string(),
+        };
+        if pomodoro.presets_get().len() > 1 {
+            pomo_mode += &format!(" ({})", pomodoro.preset_get().name);
+        }
 
         let mut widget = Paragraph::new(pomo_display).block(
             Block::default()
@@ -150,29 +600,85 @@ impl TuiRatatuiDisplay {
     fn create_not_completed_widget<'a>(
         pomodoro: &'a Pomodoro,
         current_area: &'a Area,
+        collapsed: &HashSet<u64>,
         selected_row: usize,
     ) -> Paragraph<'a> {
         let not_completed_tasks = pomodoro.task_get_by_complete(false);
+        let task_stats = pomodoro.task_stats();
+        let active_task_id = pomodoro.task_active_get().map(|task| task.id);
+        let today = Local::now().date_naive();
         let mut not_completed_tasks_vec: Vec<Line<'_>> = vec![];
 
-        for (i, task) in not_completed_tasks.iter().enumerate() {
-            let task_line = if *current_area == Area::TasksNotCompleted && i == selected_row {
+        let rows = Self::task_rows(&not_completed_tasks, collapsed);
+        for (i, row) in rows.iter().enumerate() {
+            let selected = *current_area == Area::TasksNotCompleted && i == selected_row;
+            let task = &not_completed_tasks[row.task];
+            if let Some(subtask_index) = row.subtask {
+                let subtask = &task.subtasks[subtask_index];
+                not_completed_tasks_vec.push(Self::subtask_line(subtask, selected));
+                continue;
+            }
+
+            let mut markers = format!("#{} ", task.id) + &Self::tree_marker(task, collapsed);
+            if active_task_id == Some(task.id) {
+                markers += "▶ ";
+            }
+            if let Some(priority) = task.priority {
+                markers += &format!("{priority} ");
+            }
+            let task_stats = task_stats.get(&task.id).copied().unwrap_or_default();
+            let estimate = task
+                .estimate_progress(task_stats.pomodoros)
+                .map(|progress| format!(" {progress}"))
+                .unwrap_or_default();
+            let stats = if task_stats.focus > Duration::ZERO {
+                format!(
+                    " [{} 🍅 | {}]",
+                    task_stats.pomodoros,
+                    format_hours(task_stats.focus)
+                )
+            } else {
+                String::new()
+            };
+
+            let labels = Self::task_labels(task, today);
+            let mut style = Style::default();
+            if task.is_overdue(today) {
+                style = style.fg(Color::Red);
+            }
+            if pomodoro.task_is_blocked(task) {
+                style = style.add_modifier(Modifier::DIM);
+            }
+
+            let task_line = if selected {
                 (
-                    format!("[*] {}: {}", task.name, task.description),
-                    Style::default().add_modifier(Modifier::BOLD),
+                    format!(
+                        "[*] {markers}{}: {}{labels}{estimate}{stats}",
+                        task.name, task.description
+                    ),
+                    style.add_modifier(Modifier::BOLD),
                 )
             } else {
                 (
-                    format!("[ ] {}: {}", task.name, task.description),
-                    Style::default(),
+                    format!(
+                        "[ ] {markers}{}: {}{labels}{estimate}{stats}",
+                        task.name, task.description
+                    ),
+                    style,
                 )
             };
 
             not_completed_tasks_vec.push(Span::styled(task_line.0 + "\n", task_line.1).into());
         }
 
+        let mut title = String::from("TODO");
+        if pomodoro.sort_by_priority_get() {
+            title += " (by priority)";
+        }
+        title += &Self::filter_label(pomodoro);
+        title += ":";
         let mut task_widget = Paragraph::new(not_completed_tasks_vec)
-            .block(Block::default().title("TODO:").borders(Borders::ALL));
+            .block(Block::default().title(title).borders(Borders::ALL));
         if *current_area == Area::TasksNotCompleted {
             task_widget = task_widget.blue();
         }
@@ -183,20 +689,33 @@ impl TuiRatatuiDisplay {
     fn create_completed_widget<'a>(
         pomodoro: &'a Pomodoro,
         current_area: &'a Area,
+        collapsed: &HashSet<u64>,
         selected_row: usize,
     ) -> Paragraph<'a> {
         let completed_tasks = pomodoro.task_get_by_complete(true);
+        let today = Local::now().date_naive();
         let mut completed_tasks_vec: Vec<Line<'_>> = vec![];
 
-        for (i, task) in completed_tasks.iter().enumerate() {
-            let task_line = if *current_area == Area::TasksCompleted && i == selected_row {
+        let rows = Self::task_rows(&completed_tasks, collapsed);
+        for (i, row) in rows.iter().enumerate() {
+            let selected = *current_area == Area::TasksCompleted && i == selected_row;
+            let task = &completed_tasks[row.task];
+            if let Some(subtask_index) = row.subtask {
+                let subtask = &task.subtasks[subtask_index];
+                completed_tasks_vec.push(Self::subtask_line(subtask, selected));
+                continue;
+            }
+
+            let markers = format!("#{} ", task.id) + &Self::tree_marker(task, collapsed);
+            let labels = Self::task_labels(task, today);
+            let task_line = if selected {
                 (
-                    format!("[*] {}: {}", task.name, task.description),
+                    format!("[*] {markers}{}: {}{labels}", task.name, task.description),
                     Style::default().add_modifier(Modifier::BOLD),
                 )
             } else {
                 (
-                    format!("[x] {}: {}", task.name, task.description),
+                    format!("[x] {markers}{}: {}{labels}", task.name, task.description),
                     Style::default(),
                 )
             };
@@ -204,8 +723,9 @@ impl TuiRatatuiDisplay {
             completed_tasks_vec.push(Span::styled(task_line.0 + "\n", task_line.1).into());
         }
 
+        let title = format!("DONE{}:", Self::filter_label(pomodoro));
         let mut widget = Paragraph::new(completed_tasks_vec)
-            .block(Block::default().title("DONE:").borders(Borders::ALL));
+            .block(Block::default().title(title).borders(Borders::ALL));
 
         if *current_area == Area::TasksCompleted {
             widget = widget.blue();
@@ -214,30 +734,917 @@ impl TuiRatatuiDisplay {
         widget
     }
 
-    fn create_add_task<'a>(
+    fn task_rows(tasks: &[Task], collapsed: &HashSet<u64>) -> Vec<TaskRow> {
+        let mut rows = vec![];
+        for (task_index, task) in tasks.iter().enumerate() {
+            rows.push(TaskRow {
+                task: task_index,
+                subtask: None,
+            });
+            if collapsed.contains(&task.id) {
+                continue;
+            }
+            for subtask_index in 0..task.subtasks.len() {
+                rows.push(TaskRow {
+                    task: task_index,
+                    subtask: Some(subtask_index),
+                });
+            }
+        }
+        rows
+    }
+
+    /// `▾ 1/3 ` for an expanded task with subtasks, `▸ 1/3 ` when collapsed.
+    fn tree_marker(task: &Task, collapsed: &HashSet<u64>) -> String {
+        match task.subtasks_progress() {
+            Some(progress) if collapsed.contains(&task.id) => format!("▸ {progress} "),
+            Some(progress) => format!("▾ {progress} "),
+            None => String::new(),
+        }
+    }
+
+    fn subtask_line<'a>(subtask: &Subtask, selected: bool) -> Line<'a> {
+        let (check, style) = match (selected, subtask.completed) {
+            (true, _) => ("*", Style::default().add_modifier(Modifier::BOLD)),
+            (false, true) => ("x", Style::default()),
+            (false, false) => (" ", Style::default()),
+        };
+        Span::styled(format!("    [{check}] {}", subtask.name), style).into()
+    }
+
+    /// Project, tags, dates, recurrence and blockers of a task, like
+    /// ` +work @review due:today rec:daily dep:3`.
+    fn task_labels(task: &Task, today: NaiveDate) -> String {
+        let mut labels = String::new();
+        if let Some(project) = &task.project {
+            labels += &format!(" +{project}");
+        }
+        for tag in task.tags.iter() {
+            labels += &format!(" @{tag}");
+        }
+        if let Some(scheduled) = task.scheduled {
+            labels += &format!(" sched:{}", format_date(scheduled, today));
+        }
+        if let Some(due) = task.due {
+            labels += &format!(" due:{}", format_date(due, today));
+        }
+        if let Some(recurrence) = task.recurrence {
+            labels += &format!(" rec:{recurrence}");
+        }
+        if !task.blocked_by.is_empty() {
+            labels += &format!(" dep:{}", Task::ids_string(&task.blocked_by));
+        }
+        labels
+    }
+
+    fn filter_label(pomodoro: &Pomodoro) -> String {
+        pomodoro
+            .filter_get()
+            .map(|filter| format!(" [{filter}]"))
+            .unwrap_or_default()
+    }
+
+    fn create_input_widget<'a>(current_area: &'a Area, input: &TextInput) -> Option<Paragraph<'a>> {
+        let title = match current_area {
+            Area::TaskAdd => "Task add",
+            Area::TaskEdit(_) => "Task edit",
+            Area::Filter => "Filter (+project, @tag or today, empty clears)",
+            Area::SubtaskAdd(_) => "Subtask add",
+            Area::InterruptionNote(InterruptionKind::Internal) => {
+                "Internal interruption note (optional)"
+            }
+            Area::InterruptionNote(InterruptionKind::External) => {
+                "External interruption note (optional)"
+            }
+            _ => return None,
+        };
+
+        let widget = Paragraph::new(input.text().to_string())
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .blue();
+        Some(widget)
+    }
+
+    fn create_report_widget<'a>(
+        pomodoro: &'a Pomodoro,
         current_area: &'a Area,
-        new_task_buffer: String,
     ) -> Option<Paragraph<'a>> {
-        if *current_area == Area::TaskAdd {
-            let widget = Paragraph::new(new_task_buffer)
-                .block(Block::default().title("Task add").borders(Borders::ALL))
+        if *current_area == Area::Report {
+            let report_lines: Vec<Line<'_>> = pomodoro
+                .report()
+                .to_string()
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect();
+
+            let widget = Paragraph::new(report_lines)
+                .block(Block::default().title("Report").borders(Borders::ALL))
+                .wrap(Wrap { trim: false })
                 .blue();
             return Some(widget);
         }
         None
     }
 
+    /// Whether the selected list is the completed one, if a list is selected.
+    fn selected_list(&self) -> Option<bool> {
+        match self.current_area {
+            Area::TasksNotCompleted => Some(false),
+            Area::TasksCompleted => Some(true),
+            _ => None,
+        }
+    }
+
+    fn rows(&self, completed: bool) -> Vec<TaskRow> {
+        let tasks = self.pomodoro.task_get_by_complete(completed);
+        Self::task_rows(&tasks, &self.collapsed)
+    }
+
+    fn selected_task_row(&self) -> Option<TaskRow> {
+        let completed = self.selected_list()?;
+        self.rows(completed).get(self.selected_row).copied()
+    }
+
+    /// Presets to switch to, and what to do with the running phase once one
+    /// is picked.
+    fn create_presets_widget<'a>(
+        pomodoro: &'a Pomodoro,
+        current_area: &'a Area,
+        selected_row: usize,
+    ) -> Option<Paragraph<'a>> {
+        let selected_row = match current_area {
+            Area::Presets => selected_row,
+            Area::PresetChange(index) => *index,
+            _ => return None,
+        };
+
+        let current = &pomodoro.preset_get().name;
+        let mut lines: Vec<Line<'_>> = vec![];
+        for (i, preset) in pomodoro.presets_get().iter().enumerate() {
+            let mut label = format!(
+                "{}: {} focus / {} rest",
+                preset.name,
+                format_duration(preset.focus),
+                format_duration(preset.rest)
+            );
+            if preset.long_rest_every > 0 {
+                label += &format!(
+                    " / {} long rest every {}",
+                    format_duration(preset.long_rest),
+                    preset.long_rest_every
+                );
+            }
+            if preset.name == *current {
+                label += " (current)";
+            }
+
+            let style = if i == selected_row {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Span::styled(label, style).into());
+        }
+
+        let title = if let Area::PresetChange(_) = current_area {
+            lines.push(Line::default());
+            lines.push(
+                Span::from(format!(
+                    "{} left of this {}.",
+                    pomodoro.get_current_timer(),
+                    pomodoro.get_mode().to_string().to_lowercase()
+                ))
+                .into(),
+            );
+            "k: keep time left | r: restart | s: scale | Esc: cancel"
+        } else {
+            "Timer presets (Enter: switch, Esc: close)"
+        };
+
+        let widget = Paragraph::new(lines)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .blue();
+        Some(widget)
+    }
+
+    fn create_resume_widget<'a>(
+        current_area: &'a Area,
+        resume: Option<&'a Snapshot>,
+    ) -> Option<Paragraph<'a>> {
+        let snapshot = match (current_area, resume) {
+            (Area::Resume, Some(snapshot)) => snapshot,
+            _ => return None,
+        };
+
+        let lines: Vec<Line<'_>> = vec![
+            Span::from("The last run left a timer behind:").into(),
+            Line::default(),
+            Span::from(snapshot.to_string()).bold().into(),
+        ];
+        let widget = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Resume? (y: resume, n: start over)")
+                    .borders(Borders::ALL),
+            )
+            .blue();
+        Some(widget)
+    }
+
+    fn create_idle_widget<'a>(activity_at: DateTime<Local>) -> Paragraph<'a> {
+        let lines: Vec<Line<'_>> = vec![Span::from(format!(
+            "No keys since {}. Without an answer, the timer pauses as of then.",
+            activity_at.format("%H:%M")
+        ))
+        .into()];
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title("Still working? (y: yes, n: pause)")
+                    .borders(Borders::ALL),
+            )
+            .blue()
+    }
+
+    fn create_detail_widget<'a>(
+        pomodoro: &'a Pomodoro,
+        current_area: &'a Area,
+        scroll: u16,
+    ) -> Option<Paragraph<'a>> {
+        let task = match current_area {
+            Area::TaskDetail(task_id) => pomodoro.task_get(*task_id)?,
+            _ => return None,
+        };
+        let today = Local::now().date_naive();
+        let stats = pomodoro
+            .task_stats()
+            .get(&task.id)
+            .copied()
+            .unwrap_or_default();
+        let timestamp = |at: Option<DateTime<Local>>| {
+            at.map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+
+        let mut lines: Vec<Line<'_>> = vec![Span::from(task.name.clone()).bold().into()];
+        if !task.description.is_empty() {
+            lines.push(Line::from(task.description.clone()));
+        }
+        lines.push(Line::from(format!(
+            "#{}{}",
+            task.id,
+            Self::task_labels(&task, today)
+        )));
+        lines.push(Line::from(format!(
+            "Created: {} | Completed: {}",
+            timestamp(task.created_at),
+            timestamp(task.completed_at)
+        )));
+        let mut spent = format!(
+            "Pomodoros: {} | Focus: {}",
+            stats.pomodoros,
+            format_hours(stats.focus)
+        );
+        if let Some(progress) = task.estimate_progress(stats.pomodoros) {
+            spent += &format!(" | Estimate: {progress}");
+        }
+        lines.push(Line::from(spent));
+        if let Some(progress) = task.subtasks_progress() {
+            lines.push(Line::from(format!("Subtasks: {progress}")));
+        }
+        let blockers = pomodoro.task_blockers(&task);
+        if !blockers.is_empty() {
+            let blockers: Vec<String> = blockers
+                .iter()
+                .map(|blocker| format!("#{} {}", blocker.id, blocker.name))
+                .collect();
+            lines.push(Line::from(format!("Blocked by: {}", blockers.join(", "))));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Span::from("Notes:").bold().into());
+        if task.notes.is_empty() {
+            lines.push(Span::from("No notes yet.").italic().into());
+        }
+        for line in task.notes.lines() {
+            lines.push(Line::from(line.to_string()));
+        }
+
+        let widget = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Task (e: edit notes, E: $EDITOR, Esc: close)")
+                    .borders(Borders::ALL),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .blue();
+        Some(widget)
+    }
+
+    fn create_notes_widget<'a>(current_area: &'a Area, notes: &TextInput) -> Option<Paragraph<'a>> {
+        if !matches!(current_area, Area::NotesEdit(_)) {
+            return None;
+        }
+
+        let lines: Vec<Line<'_>> = notes
+            .text()
+            .split('\n')
+            .map(|line| Line::from(line.to_string()))
+            .collect();
+        let widget = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Notes (Ctrl-s: save, Esc: cancel)")
+                    .borders(Borders::ALL),
+            )
+            .blue();
+        Some(widget)
+    }
+
+    /// Search box and results of the archive view.
+    fn create_archive_widgets<'a>(
+        pomodoro: &'a Pomodoro,
+        current_area: &'a Area,
+        input: &TextInput,
+        selected_row: usize,
+    ) -> Option<(Paragraph<'a>, Paragraph<'a>)> {
+        if *current_area != Area::Archive {
+            return None;
+        }
+
+        let search_widget = Paragraph::new(input.text().to_string())
+            .block(
+                Block::default()
+                    .title("Archive search (Enter: restore, Esc: close)")
+                    .borders(Borders::ALL),
+            )
+            .blue();
+
+        let today = Local::now().date_naive();
+        let results = pomodoro.archive_search(input.text());
+        let mut lines: Vec<Line<'_>> = vec![];
+        for (i, task) in results.iter().enumerate() {
+            let completed_at = task
+                .completed_at
+                .map(|at| format!(" ({})", format_date(at.date_naive(), today)))
+                .unwrap_or_default();
+            let line = format!(
+                "#{} {}: {}{}{completed_at}",
+                task.id,
+                task.name,
+                task.description,
+                Self::task_labels(task, today)
+            );
+            let style = if i == selected_row {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Span::styled(line, style).into());
+        }
+        if results.is_empty() {
+            lines.push(Span::from("Nothing found.").italic().into());
+        }
+
+        let title = format!("{} archived", results.len());
+        let list_widget = Paragraph::new(lines)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .blue();
+        Some((search_widget, list_widget))
+    }
+
+    /// Query box and matching actions and tasks of the command palette.
+    fn create_palette_widgets<'a>(
+        pomodoro: &'a Pomodoro,
+        current_area: &'a Area,
+        input: &TextInput,
+        selected_row: usize,
+    ) -> Option<(Paragraph<'a>, Paragraph<'a>)> {
+        if *current_area != Area::Palette {
+            return None;
+        }
+
+        let search_widget = Paragraph::new(input.text().to_string())
+            .block(
+                Block::default()
+                    .title("Go to task or run action (Enter: pick, Esc: close)")
+                    .borders(Borders::ALL),
+            )
+            .blue();
+
+        let items = palette_items(pomodoro, input.text());
+        let mut lines: Vec<Line<'_>> = vec![];
+        for (i, item) in items.iter().enumerate() {
+            let style = if i == selected_row {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Span::styled(item.label(), style).into());
+        }
+        if items.is_empty() {
+            lines.push(Span::from("Nothing found.").italic().into());
+        }
+
+        let list_widget = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL))
+            .blue();
+        Some((search_widget, list_widget))
+    }
+
+    /// The text input of the current prompt.
+    fn input(&self) -> &TextInput {
+        match self.current_area {
+            Area::Filter => &self.filter,
+            _ => &self.input,
+        }
+    }
+
+    fn input_mut(&mut self) -> &mut TextInput {
+        match self.current_area {
+            Area::Filter => &mut self.filter,
+            _ => &mut self.input,
+        }
+    }
+
+    /// The selected task, or the parent of the selected subtask.
+    fn selected_task(&self) -> Option<Task> {
+        let completed = self.selected_list()?;
+        let row = self.selected_task_row()?;
+        self.pomodoro
+            .task_get_by_complete(completed)
+            .get(row.task)
+            .cloned()
+    }
+
+    fn task_select(&mut self, completed: bool, task_index: usize) {
+        let row = self
+            .rows(completed)
+            .iter()
+            .position(|row| row.task == task_index && row.subtask.is_none());
+        if let Some(row) = row {
+            self.selected_row = row;
+        }
+    }
+
+    /// Moves the selected task up or down its list, following it with the
+    /// selection.
+    fn task_move(&mut self, up: bool) {
+        let (completed, row) = match (self.selected_list(), self.selected_task_row()) {
+            (Some(completed), Some(row)) => (completed, row),
+            _ => return,
+        };
+
+        if self.pomodoro.sort_by_priority_get() {
+            self.status_set("Turn off the priority sort to move tasks".to_string());
+            return;
+        }
+
+        if let Some(new_index) = self.pomodoro.task_move(completed, row.task, up) {
+            self.task_select(completed, new_index);
+        }
+    }
+
+    /// Expands or collapses the subtasks of the selected task, selecting the
+    /// task itself.
+    fn task_collapse_toggle(&mut self) {
+        let (completed, row, task) = match (
+            self.selected_list(),
+            self.selected_task_row(),
+            self.selected_task(),
+        ) {
+            (Some(completed), Some(row), Some(task)) => (completed, row, task),
+            _ => return,
+        };
+
+        if !self.collapsed.remove(&task.id) {
+            self.collapsed.insert(task.id);
+        }
+        self.task_select(completed, row.task);
+    }
+
+    /// Shows `message` under the timer for a few seconds.
+    fn status_set(&mut self, message: String) {
+        self.status = Some((message, SystemTime::now()));
+    }
+
+    /// Lets the user write the notes of a task in `$VISUAL` or `$EDITOR`,
+    /// saving them if the editor exits successfully. The timer can't tick
+    /// while the editor runs, so the time it took is counted afterwards.
+    fn notes_edit_external(&mut self, task_id: u64) -> io::Result<()> {
+        let mut task = match self.pomodoro.task_get(task_id) {
+            Some(task) => task,
+            None => return Ok(()),
+        };
+
+        let (path, mut file) = Self::notes_file_create(task_id)?;
+        let written = file.write_all(task.notes.as_bytes());
+        drop(file);
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut editor = editor.split_whitespace();
+        let program = editor.next().unwrap_or("vi");
+
+        let _ = stdout().execute(DisableBracketedPaste)?;
+        let _ = stdout().execute(LeaveAlternateScreen)?;
+        disable_raw_mode()?;
+        let started = Instant::now();
+        let status = Command::new(program).args(editor).arg(&path).status();
+        let blocked = started.elapsed();
+        enable_raw_mode()?;
+        let _ = stdout().execute(EnterAlternateScreen)?;
+        let _ = stdout().execute(EnableBracketedPaste)?;
+        self.terminal.clear()?;
+        self.activity_at = Local::now();
+        // Attached, the daemon kept counting. The loop counts the last
+        // second itself.
+        if self.remote.is_none() && !self.pause && self.resume.is_none() {
+            let blocked = Duration::from_secs(blocked.as_secs().saturating_sub(1));
+            if self.pomodoro.run_for(blocked) {
+                self.pause = true;
+            }
+        }
+
+        let notes = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        if status?.success() {
+            task.notes = notes?.trim_end().to_string();
+            self.pomodoro.task_update(task);
+        }
+        Ok(())
+    }
+
+    /// Creates the file to edit the notes of `task_id` in, readable only by
+    /// the user and under a name no one could have set up beforehand.
+    fn notes_file_create(task_id: u64) -> io::Result<(PathBuf, File)> {
+        let mut attempts = 0;
+        loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.subsec_nanos());
+            let name = format!(
+                "tomatoes-task-{task_id}-{}-{nanos:08x}.md",
+                std::process::id()
+            );
+            let path = std::env::temp_dir().join(name);
+            let created = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(&path);
+            match created {
+                Ok(file) => return Ok((path, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 100 => {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Goes back to the list the edited task is in, keeping the selection.
+    fn task_edit_close(&mut self, task_id: u64) {
+        self.input.clear();
+        self.current_area = match self.pomodoro.task_get(task_id) {
+            Some(task) if task.completed => Area::TasksCompleted,
+            Some(_) => Area::TasksNotCompleted,
+            None => {
+                self.selected_row = 0;
+                Area::Timer
+            }
+        };
+    }
+
+    fn palette_open(&mut self) {
+        self.input.clear();
+        self.palette_row = 0;
+        self.current_area = Area::Palette;
+    }
+
+    fn palette_handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.input.clear();
+                self.current_area = Area::Timer;
+                self.selected_row = 0;
+            }
+            KeyCode::Up => self.palette_row = self.palette_row.saturating_sub(1),
+            KeyCode::Down => {
+                let items = palette_items(&self.pomodoro, self.input.text()).len();
+                if self.palette_row + 1 < items {
+                    self.palette_row += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let items = palette_items(&self.pomodoro, self.input.text());
+                if let Some(item) = items.get(self.palette_row) {
+                    self.input.clear();
+                    self.current_area = Area::Timer;
+                    self.selected_row = 0;
+                    self.palette_run(item.clone());
+                }
+            }
+            _ => {
+                if self.input.handle_key(key) {
+                    self.palette_row = 0;
+                }
+            }
+        }
+    }
+
+    fn palette_run(&mut self, item: PaletteItem) {
+        match item {
+            PaletteItem::Task { id, completed, .. } => self.task_jump(id, completed),
+            PaletteItem::Action(action) => self.action_run(action),
+            PaletteItem::Preset(name) => {
+                let presets = self.pomodoro.presets_get();
+                if let Some(index) = presets.iter().position(|preset| preset.name == name) {
+                    self.preset_pick(index);
+                }
+            }
+        }
+    }
+
+    fn presets_open(&mut self) {
+        let current = &self.pomodoro.preset_get().name;
+        self.preset_row = self
+            .pomodoro
+            .presets_get()
+            .iter()
+            .position(|preset| preset.name == *current)
+            .unwrap_or(0);
+        self.current_area = Area::Presets;
+    }
+
+    /// Switches to the preset at `index`, asking first what to do with the
+    /// running phase when it has already started.
+    fn preset_pick(&mut self, index: usize) {
+        let timer = self.pomodoro.get_current_timer();
+        let started = !timer.elapsed().is_zero()
+            && !matches!(self.pomodoro.get_mode(), TimerType::Transitioning(_));
+        if started {
+            self.current_area = Area::PresetChange(index);
+        } else {
+            self.preset_apply(index, PhaseChange::Restart);
+        }
+    }
+
+    fn preset_apply(&mut self, index: usize, change: PhaseChange) {
+        self.current_area = Area::Timer;
+        self.selected_row = 0;
+
+        let name = match self.pomodoro.presets_get().get(index) {
+            Some(preset) => preset.name.clone(),
+            None => return,
+        };
+        if self.remote.is_some() {
+            self.remote_request(Request::Preset {
+                name,
+                change: Some(change),
+            });
+            return;
+        }
+        if self.pomodoro.preset_apply(&name, change) {
+            let preset = self.pomodoro.preset_get();
+            let message = format!(
+                "Preset {}: {} focus / {} rest",
+                preset.name,
+                format_duration(preset.focus),
+                format_duration(preset.rest)
+            );
+            self.status_set(message);
+        }
+    }
+
+    fn presets_handle_key(&mut self, key: KeyEvent) {
+        match (&self.current_area, key.code) {
+            (Area::PresetChange(_), KeyCode::Esc) => self.current_area = Area::Presets,
+            (Area::PresetChange(index), KeyCode::Char('k')) => {
+                self.preset_apply(*index, PhaseChange::Keep);
+            }
+            (Area::PresetChange(index), KeyCode::Char('r')) => {
+                self.preset_apply(*index, PhaseChange::Restart);
+            }
+            (Area::PresetChange(index), KeyCode::Char('s')) => {
+                self.preset_apply(*index, PhaseChange::Scale);
+            }
+            (Area::Presets, KeyCode::Esc) => {
+                self.current_area = Area::Timer;
+                self.selected_row = 0;
+            }
+            (Area::Presets, KeyCode::Up | KeyCode::Char('k')) => {
+                self.preset_row = self.preset_row.saturating_sub(1);
+            }
+            (Area::Presets, KeyCode::Down | KeyCode::Char('j'))
+                if self.preset_row + 1 < self.pomodoro.presets_get().len() =>
+            {
+                self.preset_row += 1;
+            }
+            (Area::Presets, KeyCode::Enter) => self.preset_pick(self.preset_row),
+            _ => {}
+        }
+    }
+
+    fn action_run(&mut self, action: PaletteAction) {
+        match action {
+            PaletteAction::StartPause => self.timer_action(0),
+            PaletteAction::Skip => self.timer_action(1),
+            PaletteAction::Reset => self.timer_action(2),
+            PaletteAction::TaskAdd => self.current_area = Area::TaskAdd,
+            PaletteAction::GoTimer => self.current_area = Area::Timer,
+            PaletteAction::GoTodo => self.current_area = Area::TasksNotCompleted,
+            PaletteAction::GoDone => self.current_area = Area::TasksCompleted,
+            PaletteAction::AlarmToggle => {
+                let message = if self.pomodoro.alarm_toggle() {
+                    "Alarm on"
+                } else {
+                    "Alarm off"
+                };
+                self.status_set(message.to_string());
+            }
+            PaletteAction::Report => self.current_area = Area::Report,
+            PaletteAction::Archive => {
+                self.archive_row = 0;
+                self.current_area = Area::Archive;
+            }
+            PaletteAction::ArchiveDone => self.archive_done(),
+            PaletteAction::TodayFilter => self.pomodoro.filter_set(Some(TaskFilter::Today)),
+            PaletteAction::FilterClear => self.pomodoro.filter_set(None),
+            PaletteAction::SortByPriority => self.pomodoro.sort_by_priority_toggle(),
+            PaletteAction::Undo => self.undo(),
+            PaletteAction::Redo => self.redo(),
+            PaletteAction::PresetSwitch => self.presets_open(),
+            PaletteAction::Quit => self.should_close = true,
+            PaletteAction::PaletteOpen => self.palette_open(),
+        }
+    }
+
+    /// Selects a task in its list, clearing the filter if it hides the task.
+    fn task_jump(&mut self, task_id: u64, completed: bool) {
+        let position = |display: &Self| {
+            display
+                .pomodoro
+                .task_get_by_complete(completed)
+                .iter()
+                .position(|task| task.id == task_id)
+        };
+        if position(self).is_none() {
+            self.pomodoro.filter_set(None);
+        }
+
+        if let Some(index) = position(self) {
+            self.current_area = if completed {
+                Area::TasksCompleted
+            } else {
+                Area::TasksNotCompleted
+            };
+            self.task_select(completed, index);
+        }
+    }
+
+    /// Runs the timer button at `col`: start/pause, skip or reset.
+    fn timer_action(&mut self, col: usize) {
+        if self.remote.is_some() {
+            let request = match col {
+                0 => Request::Toggle,
+                1 => Request::Skip,
+                2 => Request::Reset,
+                _ => return,
+            };
+            self.remote_request(request);
+            return;
+        }
+
+        match col {
+            0 => {
+                self.pause = !self.pause;
+            }
+            1 => {
+                self.pomodoro.next_mode();
+                if self.autopause {
+                    self.pause = true;
+                }
+            }
+            2 => {
+                self.pomodoro.reset_timer(self.pomodoro.get_mode());
+            }
+            _ => {}
+        }
+        self.snapshot_save();
+    }
+
+    fn undo(&mut self) {
+        let message = match self.pomodoro.undo() {
+            Some(description) => format!("Undone: {description}"),
+            None => "Nothing to undo".to_string(),
+        };
+        self.status_set(message);
+    }
+
+    fn redo(&mut self) {
+        let message = match self.pomodoro.redo() {
+            Some(description) => format!("Redone: {description}"),
+            None => "Nothing to redo".to_string(),
+        };
+        self.status_set(message);
+    }
+
+    fn archive_done(&mut self) {
+        let count = self.pomodoro.archive_done();
+        self.status_set(format!("Archived {count} done tasks"));
+        if self.current_area == Area::TasksCompleted {
+            self.current_area = Area::TasksNotCompleted;
+            self.selected_row = 0;
+        }
+    }
+
+    fn archive_handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.input.clear();
+                self.current_area = Area::Timer;
+                self.selected_row = 0;
+            }
+            KeyCode::Up => self.archive_row = self.archive_row.saturating_sub(1),
+            KeyCode::Down => {
+                let results = self.pomodoro.archive_search(self.input.text()).len();
+                if self.archive_row + 1 < results {
+                    self.archive_row += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let results = self.pomodoro.archive_search(self.input.text());
+                if let Some(task) = results.get(self.archive_row) {
+                    self.pomodoro.archive_restore(task.id);
+                    self.status_set(format!("Restored \"{}\"", task.name));
+                    self.archive_row = self.archive_row.min(results.len().saturating_sub(2));
+                }
+            }
+            _ => {
+                if self.input.handle_key(key) {
+                    self.archive_row = 0;
+                }
+            }
+        }
+    }
+
+    fn notes_handle_key(&mut self, task_id: u64, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.current_area = Area::TaskDetail(task_id),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(mut task) = self.pomodoro.task_get(task_id) {
+                    task.notes = self.notes.take().trim_end().to_string();
+                    self.pomodoro.task_update(task);
+                }
+                self.current_area = Area::TaskDetail(task_id);
+            }
+            _ => {
+                self.notes.handle_key(key);
+            }
+        }
+    }
+
     pub fn pomo_loop(&mut self) -> io::Result<()> {
         enable_raw_mode()?;
         let _ = stdout().execute(EnterAlternateScreen)?;
+        let _ = stdout().execute(EnableBracketedPaste)?;
 
-        let mut next_count = SystemTime::now();
+        // Monotonic, so the wall clock going back can't stall or panic it.
+        let mut next_count = Instant::now();
         let one_sec = Duration::from_secs(1);
         while !self.should_close {
+            // Checked even while attached, where the daemon handles sleeps,
+            // so they aren't handled again here if it goes away.
+            let slept = self.sleep_watch.check();
+            if self.remote.is_some() {
+                self.remote_sync();
+                let _ = self.display();
+                self.handle_events()?;
+                continue;
+            }
+
+            // The saved timer waits for an answer before anything runs.
+            let asking = self.resume.is_some();
+            if let Some((from, to)) = slept.filter(|_| !self.pause && !asking) {
+                self.suspended(from, to);
+                next_count = Instant::now();
+            }
+            self.idle_check();
+            // Focus doesn't end while waiting to hear whether anyone is there.
+            let held = self.idle_asked.is_some()
+                && self.pomodoro.get_current_timer().current_time <= one_sec;
             let prev_timer = self.pomodoro.get_mode();
-            if !self.pause && next_count.elapsed().unwrap() > one_sec {
+            if !self.pause && !asking && !held && next_count.elapsed() > one_sec {
                 self.pomodoro.forward();
-                next_count = SystemTime::now();
+                next_count = Instant::now();
             }
 
             // NOTE: Improve later.
@@ -255,203 +1662,467 @@ impl TuiRatatuiDisplay {
                 }
             }
 
+            if let Some(e) = self.pomodoro.alarm_error_take() {
+                self.status_set(format!("Could not play the alarm: {e}"));
+            }
+
+            let state = (self.pomodoro.get_mode(), self.pause);
+            let due = self
+                .snapshot_at
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed >= SNAPSHOT_INTERVAL);
+            if !asking && (state != self.snapshot_state || due) {
+                self.snapshot_save();
+            }
+
             let _ = self.display();
             self.handle_events()?;
         }
 
+        let _ = stdout().execute(DisableBracketedPaste)?;
         disable_raw_mode()?;
         let _ = stdout().execute(LeaveAlternateScreen)?;
 
-        let _ = self.pomodoro.save();
+        // Attached, the daemon owns the history.
+        if self.remote.is_some() {
+            self.remote_sync();
+        } else {
+            let _ = self.pomodoro.save();
+            if self.resume.is_none() {
+                self.snapshot_save();
+            }
+        }
+        self.pomodoro.hooks_wait();
 
         Ok(())
     }
 
     pub fn handle_events(&mut self) -> io::Result<()> {
         if event::poll(Duration::from_secs_f64(1f64 / 60f64))? {
-            if let Event::Key(key) = event::read()? {
-                match (key.code, key.kind) {
-                    (KeyCode::Esc, KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => {
-                            self.selected_row = 0;
-                            self.current_area = Area::Timer;
-                            self.new_task_buffer = String::new();
-                        }
-                        _ => self.should_close = true,
-                    },
-                    (KeyCode::Char(' '), KeyEventKind::Press) => {
-                        const SPACE_DELAY: Duration = Duration::from_secs(2);
-                        if let Ok(time_elapsed) = self.space_timeout.elapsed() {
-                            if time_elapsed < SPACE_DELAY {
-                                self.space_timeout = SystemTime::now();
-                                return Ok(());
-                            }
-                        }
+            let event = event::read()?;
+            // The answer to "still working?" is handled as of the last key.
+            if self.idle_asked.is_none() && matches!(event, Event::Key(_) | Event::Paste(_)) {
+                self.activity_at = Local::now();
+            }
+            match event {
+                Event::Key(key) => self.handle_key(key),
+                Event::Paste(text)
+                    if self.current_area.is_text_input()
+                        || matches!(self.current_area, Area::Archive | Area::Palette) =>
+                {
+                    self.input_mut().insert_str(&text);
+                }
+                Event::Paste(text) if matches!(self.current_area, Area::NotesEdit(_)) => {
+                    self.notes.insert_str(&text);
+                }
+                _ => {}
+            }
+        }
 
-                        match self.current_area {
-                            Area::Timer => {
-                                match self.selected_col {
-                                    0 => {
-                                        self.pause = !self.pause;
-                                    }
-                                    1 => {
-                                        self.pomodoro.next_mode();
-                                        if self.autopause {
-                                            self.pause = true;
-                                        }
-                                    }
-                                    2 => {
-                                        self.pomodoro.reset_timer(self.pomodoro.get_mode());
-                                    }
-                                    _ => {}
-                                }
-                                // self.pause = !self.pause;
-                                // self.selected_col = 0;
-                            }
-                            Area::TasksNotCompleted => {
-                                self.pomodoro.task_complete(self.selected_row);
-                            }
-                            Area::TasksCompleted => {
-                                self.pomodoro.task_not_complete(self.selected_row);
-                            }
-                            _ => {
-                                self.new_task_buffer += " ";
-                            }
-                        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind == KeyEventKind::Press {
+            self.notice = None;
+        }
+
+        if self.idle_asked.is_some() {
+            if key.kind == KeyEventKind::Press {
+                self.idle_handle_key(key);
+            }
+            return;
+        }
+
+        if self.current_area.is_text_input()
+            && key.kind == KeyEventKind::Press
+            && self.input_mut().handle_key(key)
+        {
+            return;
+        }
+
+        if let Area::NotesEdit(task_id) = self.current_area {
+            if key.kind == KeyEventKind::Press {
+                self.notes_handle_key(task_id, key);
+            }
+            return;
+        }
+
+        if self.current_area == Area::Archive {
+            if key.kind == KeyEventKind::Press {
+                self.archive_handle_key(key);
+            }
+            return;
+        }
+
+        if self.current_area == Area::Palette {
+            if key.kind == KeyEventKind::Press {
+                self.palette_handle_key(key);
+            }
+            return;
+        }
+
+        if self.current_area == Area::Resume {
+            if key.kind == KeyEventKind::Press {
+                self.resume_handle_key(key);
+            }
+            return;
+        }
+
+        if matches!(self.current_area, Area::Presets | Area::PresetChange(_)) {
+            if key.kind == KeyEventKind::Press {
+                self.presets_handle_key(key);
+            }
+            return;
+        }
+
+        if key.kind == KeyEventKind::Press && !self.current_area.is_text_input() {
+            let bound = self
+                .keys
+                .iter()
+                .find(|(_, bound)| bound.matches(&key))
+                .map(|(action, _)| *action);
+            if let Some(action) = bound {
+                self.action_run(action);
+                return;
+            }
+        }
+
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match (key.code, key.kind) {
+            (KeyCode::Esc, KeyEventKind::Press) => match self.current_area {
+                Area::TaskAdd | Area::InterruptionNote(_) | Area::Filter => {
+                    self.selected_row = 0;
+                    self.input_mut().clear();
+                    self.current_area = Area::Timer;
+                }
+                Area::TaskEdit(task_id) | Area::SubtaskAdd(task_id) | Area::TaskDetail(task_id) => {
+                    self.task_edit_close(task_id);
+                }
+                Area::Report => {
+                    self.selected_row = 0;
+                    self.current_area = Area::Timer;
+                }
+                _ => self.should_close = true,
+            },
+            (KeyCode::Char(' '), KeyEventKind::Press) => {
+                if let Ok(time_elapsed) = self.space_timeout.elapsed() {
+                    if time_elapsed < self.space_delay {
+                        self.space_timeout = SystemTime::now();
+                        return;
                     }
-                    (KeyCode::Down, KeyEventKind::Press) => match self.current_area {
-                        Area::Timer => self.current_area = Area::TasksNotCompleted,
-                        Area::TasksNotCompleted => {
-                            if self.selected_row + 1
-                                < self.pomodoro.task_get_by_complete(false).len()
-                            {
-                                self.selected_row += 1;
-                            } else {
-                                self.current_area = Area::TasksCompleted;
-                                self.selected_row = 0;
-                            }
-                        }
-                        Area::TasksCompleted => {
-                            if self.selected_row + 1
-                                < self.pomodoro.task_get_by_complete(true).len()
-                            {
-                                self.selected_row += 1;
-                            } else {
-                                self.current_area = Area::Timer;
-                                self.selected_row = 0;
-                            }
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Up, KeyEventKind::Press) => match self.current_area {
-                        Area::Timer => {
-                            self.current_area = Area::TasksCompleted;
-                            self.selected_row =
-                                (self.pomodoro.task_get_by_complete(true).len() - 1).max(0);
-                        }
-                        Area::TasksNotCompleted => {
-                            if self.selected_row > 0 {
-                                self.selected_row -= 1;
-                            } else {
-                                self.current_area = Area::Timer;
-                                self.selected_row = 0;
-                            }
-                        }
-                        Area::TasksCompleted => {
-                            if self.selected_row > 0 {
-                                self.selected_row -= 1;
-                            } else {
-                                self.current_area = Area::TasksNotCompleted;
-                                self.selected_row =
-                                    (self.pomodoro.task_get_by_complete(false).len() - 1).max(0);
-                            }
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Left, KeyEventKind::Press) => match self.current_area {
-                        Area::Timer => {
-                            if self.selected_col == 0 {
-                                self.selected_col = COL_SIZE - 1;
-                            } else {
-                                self.selected_col -= 1;
-                            }
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Right, KeyEventKind::Press) => match self.current_area {
-                        Area::Timer => {
-                            self.selected_col += 1;
-                            self.selected_col = self.selected_col % COL_SIZE;
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Char('+'), KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => {}
-                        _ => self.current_area = Area::TaskAdd,
-                    },
-                    (KeyCode::Char('c'), KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => self.new_task_buffer += "c",
-                        Area::TasksCompleted => {
-                            let mut copy_task =
-                                self.pomodoro.task_get_by_complete(true)[self.selected_row].clone();
-                            copy_task.completed = false;
-                            self.pomodoro.task_add(copy_task);
-                        }
-                        Area::TasksNotCompleted => {
-                            let copy_task = self.pomodoro.task_get_by_complete(false)
-                                [self.selected_row]
-                                .clone();
-                            self.pomodoro.task_add(copy_task);
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Char('r'), KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => self.new_task_buffer += "r",
-                        Area::TasksCompleted => {
-                            let copy_task =
-                                self.pomodoro.task_get_by_complete(true)[self.selected_row].clone();
-                            self.pomodoro.task_remove_by_attributes(copy_task);
-                        }
-                        Area::TasksNotCompleted => {
-                            let copy_task = self.pomodoro.task_get_by_complete(false)
-                                [self.selected_row]
-                                .clone();
-                            self.pomodoro.task_remove_by_attributes(copy_task);
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Char(c), KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => {
-                            self.new_task_buffer += c.to_string().as_str();
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Enter, KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => {
-                            let new_task = Task::from_str(self.new_task_buffer.as_str());
-                            self.pomodoro.task_add(new_task);
-
-                            self.new_task_buffer = String::new();
-                            self.current_area = Area::Timer;
-                            self.selected_row = 0;
-                        }
-                        _ => {}
-                    },
-                    (KeyCode::Backspace, KeyEventKind::Press) => match self.current_area {
-                        Area::TaskAdd => {
-                            let mut buffer_str = self.new_task_buffer.chars();
-                            let _ = buffer_str.next_back();
+                }
 
-                            self.new_task_buffer = buffer_str.collect();
+                match self.current_area {
+                    Area::Timer => {
+                        self.timer_action(self.selected_col);
+                        // self.pause = !self.pause;
+                        // self.selected_col = 0;
+                    }
+                    Area::TasksNotCompleted | Area::TasksCompleted => {
+                        let completed = self.current_area == Area::TasksCompleted;
+                        match self.selected_task_row() {
+                            Some(TaskRow {
+                                task,
+                                subtask: Some(subtask),
+                            }) => self.pomodoro.subtask_toggle(completed, task, subtask),
+                            Some(row) if completed => self.pomodoro.task_not_complete(row.task),
+                            Some(row) => self.pomodoro.task_complete(row.task),
+                            None => {}
                         }
-                        _ => {}
-                    },
-                    // TODO: Add task remove feature.
+                    }
                     _ => {}
                 }
             }
-        }
+            (KeyCode::Up | KeyCode::Char('k'), KeyEventKind::Press)
+                if matches!(self.current_area, Area::TaskDetail(_)) =>
+            {
+                self.detail_scroll = self.detail_scroll.saturating_sub(1);
+            }
+            (KeyCode::Down | KeyCode::Char('j'), KeyEventKind::Press)
+                if matches!(self.current_area, Area::TaskDetail(_)) =>
+            {
+                self.detail_scroll += 1;
+            }
+            (KeyCode::Char('e'), KeyEventKind::Press)
+                if matches!(self.current_area, Area::TaskDetail(_)) =>
+            {
+                if let Area::TaskDetail(task_id) = self.current_area {
+                    if let Some(task) = self.pomodoro.task_get(task_id) {
+                        self.notes.set(task.notes);
+                        self.current_area = Area::NotesEdit(task_id);
+                    }
+                }
+            }
+            (KeyCode::Char('E'), KeyEventKind::Press) => {
+                if let Area::TaskDetail(task_id) = self.current_area {
+                    if let Err(e) = self.notes_edit_external(task_id) {
+                        self.status_set(format!("Could not edit the notes: {e}"));
+                    }
+                }
+            }
+            (KeyCode::Char('A'), KeyEventKind::Press) => self.archive_done(),
+            (KeyCode::Char('/'), KeyEventKind::Press) => self.palette_open(),
+            (KeyCode::Char('p'), KeyEventKind::Press)
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !self.current_area.is_text_input() =>
+            {
+                self.palette_open();
+            }
+            (KeyCode::Char('H'), KeyEventKind::Press) => {
+                self.input.clear();
+                self.archive_row = 0;
+                self.current_area = Area::Archive;
+            }
+            (KeyCode::Char('i'), KeyEventKind::Press) => {
+                if let Some(task) = self.selected_task() {
+                    self.detail_scroll = 0;
+                    self.current_area = Area::TaskDetail(task.id);
+                }
+            }
+            (KeyCode::Up, KeyEventKind::Press) if shift => self.task_move(true),
+            (KeyCode::Down, KeyEventKind::Press) if shift => self.task_move(false),
+            (KeyCode::Char('K'), KeyEventKind::Press) => self.task_move(true),
+            (KeyCode::Char('J'), KeyEventKind::Press) => self.task_move(false),
+            (KeyCode::Char('p'), KeyEventKind::Press) => {
+                if let (Area::TasksNotCompleted, Some(row), Some(task)) = (
+                    &self.current_area,
+                    self.selected_task_row(),
+                    self.selected_task(),
+                ) {
+                    let priority = Priority::cycle(task.priority);
+                    self.pomodoro.task_priority_set(row.task, priority);
+                }
+            }
+            (KeyCode::Char('P'), KeyEventKind::Press) => {
+                self.pomodoro.sort_by_priority_toggle();
+            }
+            (KeyCode::Down, KeyEventKind::Press) => match self.current_area {
+                Area::Timer => self.current_area = Area::TasksNotCompleted,
+                Area::TasksNotCompleted => {
+                    if self.selected_row + 1 < self.rows(false).len() {
+                        self.selected_row += 1;
+                    } else {
+                        self.current_area = Area::TasksCompleted;
+                        self.selected_row = 0;
+                    }
+                }
+                Area::TasksCompleted => {
+                    if self.selected_row + 1 < self.rows(true).len() {
+                        self.selected_row += 1;
+                    } else {
+                        self.current_area = Area::Timer;
+                        self.selected_row = 0;
+                    }
+                }
+                _ => {}
+            },
+            (KeyCode::Up, KeyEventKind::Press) => match self.current_area {
+                Area::Timer => {
+                    self.current_area = Area::TasksCompleted;
+                    self.selected_row = self.rows(true).len().saturating_sub(1);
+                }
+                Area::TasksNotCompleted => {
+                    if self.selected_row > 0 {
+                        self.selected_row -= 1;
+                    } else {
+                        self.current_area = Area::Timer;
+                        self.selected_row = 0;
+                    }
+                }
+                Area::TasksCompleted => {
+                    if self.selected_row > 0 {
+                        self.selected_row -= 1;
+                    } else {
+                        self.current_area = Area::TasksNotCompleted;
+                        self.selected_row = self.rows(false).len().saturating_sub(1);
+                    }
+                }
+                _ => {}
+            },
+            (KeyCode::Left, KeyEventKind::Press) => match self.current_area {
+                Area::Timer => {
+                    if self.selected_col == 0 {
+                        self.selected_col = COL_SIZE - 1;
+                    } else {
+                        self.selected_col -= 1;
+                    }
+                }
+                _ => {}
+            },
+            (KeyCode::Right, KeyEventKind::Press) => match self.current_area {
+                Area::Timer => {
+                    self.selected_col += 1;
+                    self.selected_col = self.selected_col % COL_SIZE;
+                }
+                _ => {}
+            },
+            (KeyCode::Char('+'), KeyEventKind::Press) => {
+                self.current_area = Area::TaskAdd;
+            }
+            (KeyCode::Char('c'), KeyEventKind::Press) => {
+                if let Some(task) = self.selected_task() {
+                    self.pomodoro.task_add(task.duplicate());
+                }
+            }
+            (KeyCode::Char('u'), KeyEventKind::Press) => self.undo(),
+            (KeyCode::Char('r'), KeyEventKind::Press)
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !self.current_area.is_text_input() =>
+            {
+                self.redo();
+            }
+            (KeyCode::Char('r'), KeyEventKind::Press) => {
+                match (self.selected_list(), self.selected_task_row()) {
+                    (
+                        Some(completed),
+                        Some(TaskRow {
+                            task,
+                            subtask: Some(subtask),
+                        }),
+                    ) => self.pomodoro.subtask_remove(completed, task, subtask),
+                    _ => {
+                        if let Some(task) = self.selected_task() {
+                            self.pomodoro.task_remove_by_attributes(task);
+                        }
+                    }
+                }
+            }
+            (KeyCode::Char('o'), KeyEventKind::Press) => self.task_collapse_toggle(),
+            (KeyCode::Char('m'), KeyEventKind::Press) => self.presets_open(),
+            (KeyCode::Char('n'), KeyEventKind::Press) => {
+                if let Some(task) = self.selected_task() {
+                    self.collapsed.remove(&task.id);
+                    self.current_area = Area::SubtaskAdd(task.id);
+                }
+            }
+            (KeyCode::Char('a'), KeyEventKind::Press)
+                if self.current_area == Area::TasksNotCompleted =>
+            {
+                if let (Some(row), Some(task)) = (self.selected_task_row(), self.selected_task()) {
+                    if !self.pomodoro.task_activate(row.task) {
+                        let blockers: Vec<String> = self
+                            .pomodoro
+                            .task_blockers(&task)
+                            .iter()
+                            .map(|blocker| format!("#{} {}", blocker.id, blocker.name))
+                            .collect();
+                        self.status_set(format!("Blocked by {}", blockers.join(", ")));
+                    }
+                }
+            }
+            (KeyCode::Char('>'), KeyEventKind::Press)
+                if self.current_area == Area::TasksNotCompleted =>
+            {
+                if let (Some(row), Some(task)) = (self.selected_task_row(), self.selected_task()) {
+                    let estimate = task.estimate.unwrap_or(0).saturating_add(1);
+                    self.pomodoro.task_estimate_set(row.task, Some(estimate));
+                }
+            }
+            (KeyCode::Char('<'), KeyEventKind::Press)
+                if self.current_area == Area::TasksNotCompleted =>
+            {
+                if let (Some(row), Some(task)) = (self.selected_task_row(), self.selected_task()) {
+                    let estimate = task.estimate.unwrap_or(0).saturating_sub(1);
+                    self.pomodoro.task_estimate_set(
+                        row.task,
+                        Some(estimate).filter(|estimate| *estimate > 0),
+                    );
+                }
+            }
+            (KeyCode::Char('s'), KeyEventKind::Press) => {
+                self.current_area = match self.current_area {
+                    Area::Report => Area::Timer,
+                    _ => Area::Report,
+                };
+                self.selected_row = 0;
+            }
+            (KeyCode::Char('\'' | '-' | 'v'), KeyEventKind::Press) if self.remote.is_some() => {
+                self.status_set(ATTACHED_ONLY.to_string());
+            }
+            (KeyCode::Char('\''), KeyEventKind::Press)
+                if self.pomodoro.get_mode() == TimerType::Focus =>
+            {
+                self.current_area = Area::InterruptionNote(InterruptionKind::Internal);
+            }
+            (KeyCode::Char('-'), KeyEventKind::Press)
+                if self.pomodoro.get_mode() == TimerType::Focus =>
+            {
+                self.current_area = Area::InterruptionNote(InterruptionKind::External);
+            }
+            (KeyCode::Char('e'), KeyEventKind::Press) => {
+                if let Some(task) = self.selected_task() {
+                    self.input.set(task.to_input_string());
+                    self.current_area = Area::TaskEdit(task.id);
+                }
+            }
+            (KeyCode::Char('f'), KeyEventKind::Press) => {
+                let filter = self.pomodoro.filter_get();
+                self.filter
+                    .set(filter.map(|filter| filter.to_string()).unwrap_or_default());
+                self.current_area = Area::Filter;
+            }
+            (KeyCode::Char('t'), KeyEventKind::Press) => {
+                let filter = match self.pomodoro.filter_get() {
+                    Some(TaskFilter::Today) => None,
+                    _ => Some(TaskFilter::Today),
+                };
+                self.pomodoro.filter_set(filter);
+                self.selected_row = 0;
+                if self.selected_list().is_some() {
+                    self.current_area = Area::TasksNotCompleted;
+                }
+            }
+            (KeyCode::Char('v'), KeyEventKind::Press) => {
+                self.pomodoro.void_current();
+                if self.autopause {
+                    self.pause = true;
+                }
+            }
+            (KeyCode::Enter, KeyEventKind::Press) => match self.current_area {
+                Area::TaskAdd => {
+                    let new_task = Task::from_str(self.input.submit().as_str());
+                    self.pomodoro.task_add(new_task);
 
-        Ok(())
+                    self.current_area = Area::Timer;
+                    self.selected_row = 0;
+                }
+                Area::InterruptionNote(kind) => {
+                    let note =
+                        Some(self.input.take().trim().to_string()).filter(|note| !note.is_empty());
+                    self.pomodoro.interruption_add(kind, note);
+
+                    self.current_area = Area::Timer;
+                    self.selected_row = 0;
+                }
+                Area::Filter => {
+                    let filter = TaskFilter::parse(&self.filter.submit());
+                    self.pomodoro.filter_set(filter);
+
+                    self.current_area = Area::Timer;
+                    self.selected_row = 0;
+                }
+                Area::SubtaskAdd(task_id) => {
+                    let name = self.input.take().trim().to_string();
+                    if let Some(mut task) = self.pomodoro.task_get(task_id) {
+                        if !name.is_empty() {
+                            task.subtasks.push(Subtask::new(name));
+                            self.pomodoro.task_update(task);
+                        }
+                    }
+                    self.task_edit_close(task_id);
+                }
+                Area::TaskEdit(task_id) => {
+                    if let Some(mut task) = self.pomodoro.task_get(task_id) {
+                        task.edit_from_str(self.input.text());
+                        self.pomodoro.task_update(task);
+                    }
+                    self.task_edit_close(task_id);
+                }
+                _ => {}
+            },
+            // TODO: Add task remove feature.
+            _ => {}
+        }
     }
 }
@@ -0,0 +1,125 @@
+use crate::backend::{fuzzy_score, Pomodoro};
+use crate::config::PaletteAction;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteItem {
+    Action(PaletteAction),
+    Preset(String),
+    Task {
+        id: u64,
+        completed: bool,
+        label: String,
+    },
+}
+
+impl PaletteItem {
+    pub fn label(&self) -> String {
+        match self {
+            PaletteItem::Action(action) => format!("> {}", action.label()),
+            PaletteItem::Preset(name) => format!("~ Preset: {name}"),
+            PaletteItem::Task {
+                completed, label, ..
+            } => {
+                let check = if *completed { "x" } else { " " };
+                format!("[{check}] {label}")
+            }
+        }
+    }
+}
+
+/// Actions, presets and tasks matching `query`, best matches first. On ties,
+/// as with an empty query, they keep that order.
+pub fn palette_items(pomodoro: &Pomodoro, query: &str) -> Vec<PaletteItem> {
+    let actions = PaletteAction::ALL.into_iter().map(PaletteItem::Action);
+    let presets = pomodoro
+        .presets_get()
+        .iter()
+        .map(|preset| PaletteItem::Preset(preset.name.clone()));
+    let tasks = [false, true].into_iter().flat_map(|completed| {
+        pomodoro
+            .tasks_get()
+            .into_iter()
+            .filter(move |task| task.completed == completed)
+            .map(move |task| PaletteItem::Task {
+                id: task.id,
+                completed,
+                label: format!("#{} {}", task.id, task.to_input_string()),
+            })
+    });
+
+    let mut items: Vec<(u32, PaletteItem)> = actions
+        .chain(presets)
+        .chain(tasks)
+        .filter_map(|item| {
+            let text = match &item {
+                PaletteItem::Action(action) => action.label().to_string(),
+                PaletteItem::Preset(_) => item.label(),
+                PaletteItem::Task { label, .. } => label.clone(),
+            };
+            Some((fuzzy_score(query, &text)?, item))
+        })
+        .collect();
+
+    items.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::{Preset, Task};
+    use std::time::Duration;
+
+    mod palette_items {
+        use super::*;
+
+        fn pomodoro() -> Pomodoro {
+            let mut pomodoro = Pomodoro::new(Duration::from_secs(15), Duration::from_secs(15));
+            pomodoro.task_add(Task::new("Write report", ""));
+            pomodoro.task_add(Task::new("Skim mail", ""));
+            pomodoro.task_complete(1);
+            pomodoro.presets_set(vec![Preset::new(
+                "deep",
+                Duration::from_secs(50 * 60),
+                Duration::from_secs(10 * 60),
+            )]);
+            pomodoro
+        }
+
+        #[test]
+        fn should_list_actions_then_tasks_without_a_query() {
+            let items = palette_items(&pomodoro(), "");
+
+            assert_eq!(items.len(), PaletteAction::ALL.len() + 3);
+            assert_eq!(items[0], PaletteItem::Action(PaletteAction::StartPause));
+            assert_eq!(
+                items[PaletteAction::ALL.len()],
+                PaletteItem::Preset("deep".to_string())
+            );
+            assert!(matches!(
+                items[PaletteAction::ALL.len() + 1],
+                PaletteItem::Task {
+                    completed: false,
+                    ..
+                }
+            ));
+        }
+
+        #[test]
+        fn should_match_tasks_and_actions() {
+            let items = palette_items(&pomodoro(), "skip");
+
+            assert_eq!(items[0], PaletteItem::Action(PaletteAction::Skip));
+            assert_eq!(items.len(), 1);
+
+            let items = palette_items(&pomodoro(), "mail");
+            assert!(matches!(
+                items[0],
+                PaletteItem::Task {
+                    completed: true,
+                    ..
+                }
+            ));
+        }
+    }
+}
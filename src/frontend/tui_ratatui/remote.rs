@@ -0,0 +1,60 @@
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use crate::daemon::{Client, Event, Request, Response, Status};
+
+/// The daemon running the timer, when the TUI is attached to one. Tasks are
+/// still edited here and handed over through the data files.
+pub struct Remote {
+    client: Client,
+    events: Receiver<Event>,
+    /// `Pomodoro::revision` when the tasks were last handed over.
+    pub revision: u64,
+    /// `Tasks` events still to come back from our own reloads.
+    echoes: usize,
+}
+
+impl Remote {
+    pub fn connect(socket: &Path, revision: u64) -> io::Result<(Self, Status)> {
+        let client = Client::connect(socket)?;
+        let (status, events) = Client::connect(socket)?.subscribe()?;
+        let remote = Self {
+            client,
+            events,
+            revision,
+            echoes: 0,
+        };
+        Ok((remote, status))
+    }
+
+    /// Sends `request`, turning a failed one into its error message.
+    pub fn request(&mut self, request: &Request) -> Result<Response, String> {
+        match self.client.request(request) {
+            Ok(response) if response.ok => Ok(response),
+            Ok(response) => Err(response.error.unwrap_or_default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Has the daemon read the saved tasks again.
+    pub fn reload(&mut self) -> Result<(), String> {
+        self.request(&Request::Reload)?;
+        self.echoes += 1;
+        Ok(())
+    }
+
+    /// The events since the last call, without the ones our own reloads
+    /// caused. `None` once the daemon is gone.
+    pub fn events(&mut self) -> Option<Vec<Event>> {
+        let mut events = vec![];
+        loop {
+            match self.events.try_recv() {
+                Ok(Event::Tasks) if self.echoes > 0 => self.echoes -= 1,
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => return Some(events),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+}
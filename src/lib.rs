@@ -1,2 +1,4 @@
 pub mod backend;
+pub mod config;
+pub mod daemon;
 pub mod frontend;